@@ -0,0 +1,45 @@
+//! Conversions between Nibbles and `num_bigint::BigUint`, enabled by the `bigint` feature
+//!
+//! These are free functions rather than `From` impls, because neither
+//! `&[Nibble]` nor `Vec<Nibble>` count as local types for the orphan rule.
+
+use num_bigint::BigUint;
+
+use crate::nibble::Nibble;
+
+/// Reads `nibbles` as the hexadecimal digits of a number, most significant
+/// nibble first, so `[1, 0, 15]` is 0x10F. An empty slice is zero.
+pub fn nibbles_to_biguint(nibbles: &[Nibble]) -> BigUint {
+    nibbles.iter().fold(BigUint::ZERO, |n, nibble| {
+        n << 4 | BigUint::from(nibble.get())
+    })
+}
+
+/// Returns the hexadecimal digits of `n` as Nibbles, most significant
+/// nibble first and without leading zeros, so zero is a single zero Nibble
+pub fn biguint_to_nibbles(n: &BigUint) -> Vec<Nibble> {
+    n.to_radix_be(16)
+        .into_iter()
+        .map(|digit| Nibble::new(digit).expect("a base 16 digit is below 16"))
+        .collect()
+}
+
+#[test]
+fn biguint_round_trip_works() {
+    let nibbles: Vec<Nibble> = [1, 0, 15, 7, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3]
+        .map(|n| Nibble::new(n).unwrap())
+        .to_vec();
+
+    let n = nibbles_to_biguint(&nibbles);
+    assert_eq!(n, BigUint::from(0x10f7_0000_0000_0000_0003_u128));
+    assert_eq!(biguint_to_nibbles(&n), nibbles);
+
+    assert_eq!(nibbles_to_biguint(&[]), BigUint::ZERO);
+    assert_eq!(biguint_to_nibbles(&BigUint::ZERO), [Nibble::MIN]);
+
+    let leading_zeros = [0, 0, 9].map(|n| Nibble::new(n).unwrap());
+    assert_eq!(
+        biguint_to_nibbles(&nibbles_to_biguint(&leading_zeros)),
+        [Nibble::new(9).unwrap()]
+    );
+}
@@ -0,0 +1,157 @@
+//! Everything related to the BitArray struct
+
+use std::ops::{Add, BitAnd, BitOr, BitXor, Not};
+
+use crate::{
+    nibble::Nibble,
+    u1::{b0, u1},
+};
+
+/// A fixed number of bits, least significant bit first
+/// (the same order as [`bits_of_u8`](crate::bits_of_u8))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitArray<const N: usize>(pub [u1; N]);
+
+impl<const N: usize> BitArray<N> {
+    /// Returns the number of bits that are one
+    pub fn count_ones(self) -> u32 {
+        self.0.iter().filter(|bit| bit.0).count() as u32
+    }
+
+    /// Returns the bits in the opposite order, so the least significant bit
+    /// becomes the most significant
+    pub fn reverse_bits(self) -> Self {
+        let mut bits = self.0;
+        bits.reverse();
+        Self(bits)
+    }
+
+    /// Adds `rhs` and a carry with a ripple-carry adder, returning the sum
+    /// and the carry out of the top bit
+    pub fn carrying_add(self, rhs: Self, carry_in: u1) -> (Self, u1) {
+        let (sum, carry) = crate::bits::ripple(self.0, rhs.0, carry_in);
+        (Self(sum), carry)
+    }
+}
+
+impl<const N: usize> Default for BitArray<N> {
+    fn default() -> Self {
+        Self([b0; N])
+    }
+}
+
+impl<const N: usize> Not for BitArray<N> {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        Self(self.0.map(|bit| !bit))
+    }
+}
+
+impl<const N: usize> BitAnd for BitArray<N> {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| self.0[i] & rhs.0[i]))
+    }
+}
+
+impl<const N: usize> BitOr for BitArray<N> {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| self.0[i] | rhs.0[i]))
+    }
+}
+
+impl<const N: usize> BitXor for BitArray<N> {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self(std::array::from_fn(|i| self.0[i] ^ rhs.0[i]))
+    }
+}
+
+/// Wrapping addition, the carry out of the top bit is discarded
+impl<const N: usize> Add for BitArray<N> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.carrying_add(rhs, b0).0
+    }
+}
+
+/// [`Nibble`] stays its own type rather than an alias of `BitArray<4>`, so
+/// it keeps its inherent API. The two convert freely and agree on every
+/// operation they share.
+impl From<Nibble> for BitArray<4> {
+    fn from(value: Nibble) -> Self {
+        Self(value.bits())
+    }
+}
+
+impl From<BitArray<4>> for Nibble {
+    fn from(value: BitArray<4>) -> Self {
+        Nibble::from_bits(value.0)
+    }
+}
+
+#[test]
+fn gates_work_at_multiple_widths() {
+    use crate::u1::b1;
+
+    let a = BitArray([b1, b0, b1]);
+    let b = BitArray([b1, b1, b0]);
+    assert_eq!((a & b).0, [b1, b0, b0]);
+    assert_eq!((a | b).0, [b1, b1, b1]);
+    assert_eq!((a ^ b).0, [b0, b1, b1]);
+    assert_eq!((!a).0, [b0, b1, b0]);
+
+    let byte = BitArray(crate::bits_of_u8(0b1100_1010));
+    assert_eq!(byte.count_ones(), 4);
+    assert_eq!(crate::u8_from_bits(byte.reverse_bits().0), 0b0101_0011);
+    assert_eq!(BitArray::<0>::default().count_ones(), 0);
+}
+
+#[test]
+fn add_works_at_multiple_widths() {
+    use crate::u1::b1;
+
+    let byte = |n| BitArray(crate::bits_of_u8(n));
+    assert_eq!(byte(200) + byte(100), byte(44));
+    assert_eq!(byte(200).carrying_add(byte(55), b1), (byte(0), b1));
+
+    let two_bits = BitArray([b1, b0]);
+    assert_eq!(
+        two_bits.carrying_add(two_bits, b0),
+        (BitArray([b0, b1]), b0)
+    );
+}
+
+#[test]
+fn nibble_round_trip_works() {
+    for i in 0..16 {
+        let nibble = Nibble::new(i).unwrap();
+        let bits = BitArray::from(nibble);
+        assert_eq!(bits.count_ones(), u32::from(i).count_ones());
+        assert_eq!(Nibble::from(bits), nibble);
+    }
+}
+
+#[test]
+fn nibble_operations_agree_with_bit_array() {
+    for a in 0..16 {
+        let nibble = Nibble::new(a).unwrap();
+        let bits = BitArray::from(nibble);
+        assert_eq!(Nibble::from(!bits), !nibble);
+        assert_eq!(bits.count_ones(), nibble.get().count_ones());
+        assert_eq!(
+            Nibble::from(bits.reverse_bits()).get(),
+            nibble.get().reverse_bits() >> 4
+        );
+
+        for b in 0..16 {
+            let other = Nibble::new(b).unwrap();
+            let other_bits = BitArray::from(other);
+            assert_eq!(Nibble::from(bits & other_bits), nibble & other);
+            assert_eq!(Nibble::from(bits | other_bits), nibble | other);
+            assert_eq!(Nibble::from(bits ^ other_bits), nibble ^ other);
+            assert_eq!(Nibble::from(bits + other_bits), nibble + other);
+        }
+    }
+}
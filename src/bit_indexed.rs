@@ -0,0 +1,58 @@
+//! Everything related to the BitIndexed struct
+
+use std::ops::Index;
+
+use num_traits::PrimInt;
+
+use crate::u1::{b0, b1, u1};
+
+/// Wrapper around an integer that can be indexed by bit position,
+/// so `BitIndexed(0b100_u32)[2]` is `b1`.
+///
+/// `Index` has to return a reference, and there is no `u1` stored inside the
+/// integer to point at. Indexing instead returns a reference to one of the
+/// constants [`b0`] and [`b1`]. [`BitIndexed::bit`] returns the bit by value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BitIndexed<T>(pub T);
+
+impl<T: PrimInt> BitIndexed<T> {
+    /// Returns the bit at position `n`, where position 0 is the least
+    /// significant bit
+    ///
+    /// # Panics
+    /// Panics if `n` is not less than the number of bits in `T`
+    pub fn bit(&self, n: usize) -> u1 {
+        let width = T::zero().count_zeros() as usize;
+        assert!(
+            n < width,
+            "bit {n} is out of bounds for a {width}-bit integer"
+        );
+        u1((self.0 >> n) & T::one() == T::one())
+    }
+}
+
+impl<T: PrimInt> Index<usize> for BitIndexed<T> {
+    type Output = u1;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.bit(index) {
+            b1 => &b1,
+            _ => &b0,
+        }
+    }
+}
+
+#[test]
+fn indexing_works() {
+    let register = BitIndexed(0x8000_0005_u32);
+    assert_eq!([register[0], register[1], register[2]], [b1, b0, b1]);
+    assert_eq!(register[31], b1);
+    assert_eq!(register.bit(30), b0);
+    assert_eq!(BitIndexed(-1_i8)[7], b1);
+}
+
+#[test]
+#[should_panic]
+fn indexing_out_of_bounds_panics() {
+    let _ = BitIndexed(0_u32)[32];
+}
@@ -0,0 +1,174 @@
+//! Everything related to the BitWriter and BitReader structs
+//!
+//! Both work most significant bit first: the first bit written goes into the
+//! top bit of the first byte, and multi-bit values are written from their
+//! most significant bit down.
+
+use crate::u1::u1;
+
+/// Builds a stream of bits packed into bytes
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BitWriter {
+    /// Packed bits, any unwritten bits in the last byte are zero
+    bytes: Vec<u8>,
+
+    /// Number of bits written
+    len: usize,
+}
+
+impl BitWriter {
+    /// Returns an empty BitWriter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of bits written so far
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no bits have been written
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a single bit
+    pub fn write_bit(&mut self, bit: u1) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit.0 {
+            self.bytes[self.len / 8] |= 0b1000_0000 >> (self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    /// Appends the lowest `count` bits of `value`, most significant first
+    ///
+    /// # Panics
+    /// Panics if `count` is greater than 32
+    pub fn write_bits(&mut self, value: u32, count: u32) {
+        assert!(count <= u32::BITS, "can't write {count} bits from a u32");
+        for i in (0..count).rev() {
+            self.write_bit(u1(value & 1 << i > 0));
+        }
+    }
+
+    /// Returns the bytes written so far, with the last byte zero-padded
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the writer, returning its bytes with the last byte zero-padded
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads a stream of bits out of bytes, such as those produced by a [`BitWriter`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+
+    /// Index of the next bit to read
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Returns a BitReader positioned at the first bit of `bytes`
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    /// Reads the next bit, or returns `None` at the end of the stream
+    pub fn read_bit(&mut self) -> Option<u1> {
+        let byte = self.bytes.get(self.position / 8)?;
+        let bit = u1(byte & 0b1000_0000 >> (self.position % 8) > 0);
+        self.position += 1;
+        Some(bit)
+    }
+
+    /// Reads the next `count` bits as a number whose most significant bit
+    /// was read first. Returns `None` without consuming anything if fewer
+    /// than `count` bits are left.
+    ///
+    /// # Panics
+    /// Panics if `count` is greater than 32
+    pub fn read_bits(&mut self, count: u32) -> Option<u32> {
+        assert!(count <= u32::BITS, "can't read {count} bits into a u32");
+        if self.bits_remaining() < count as usize {
+            return None;
+        }
+        (0..count).try_fold(0, |value, _| {
+            self.read_bit().map(|bit| value << 1 | u32::from(bit))
+        })
+    }
+
+    /// Returns the next `count` bits like [`BitReader::read_bits`],
+    /// but without advancing the reader
+    ///
+    /// # Panics
+    /// Panics if `count` is greater than 32
+    pub fn peek_bits(&self, count: u32) -> Option<u32> {
+        self.clone().read_bits(count)
+    }
+
+    /// Returns the number of bits left to read, including any padding
+    /// in the last byte
+    pub fn bits_remaining(&self) -> usize {
+        (self.bytes.len() * 8).saturating_sub(self.position)
+    }
+
+    /// Skips to the start of the next byte, doing nothing if the reader is
+    /// already on a byte boundary
+    pub fn align_to_byte(&mut self) {
+        self.position = self.position.next_multiple_of(8);
+    }
+}
+
+#[test]
+fn round_trip_across_byte_boundaries_works() {
+    let fields = [
+        (0b101, 3),
+        (0x3ff, 10),
+        (0, 1),
+        (0xdead_beef, 32),
+        (1, 1),
+        (0x15, 5),
+    ];
+
+    let mut writer = BitWriter::new();
+    for (value, count) in fields {
+        writer.write_bits(value, count);
+    }
+    assert_eq!(writer.len(), 52);
+    let bytes = writer.into_bytes();
+    assert_eq!(bytes.len(), 7);
+    assert_eq!(bytes[..2], [0b1011_1111, 0b1111_1011]);
+
+    let mut reader = BitReader::new(&bytes);
+    for (value, count) in fields {
+        assert_eq!(reader.read_bits(count), Some(value));
+    }
+    // Only the four padding bits are left
+    assert_eq!(reader.read_bits(5), None);
+    assert_eq!(reader.read_bits(4), Some(0));
+    assert_eq!(reader.read_bit(), None);
+}
+
+#[test]
+fn peek_and_align_work() {
+    let bytes = [0b1011_0010, 0b1100_0000];
+    let mut reader = BitReader::new(&bytes);
+
+    assert_eq!(reader.peek_bits(3), Some(0b101));
+    assert_eq!(reader.read_bits(3), Some(0b101));
+    assert_eq!(reader.bits_remaining(), 13);
+
+    reader.align_to_byte();
+    assert_eq!(reader.bits_remaining(), 8);
+    reader.align_to_byte();
+    assert_eq!(reader.bits_remaining(), 8);
+    assert_eq!(reader.peek_bits(9), None);
+    assert_eq!(reader.read_bits(2), Some(0b11));
+}
@@ -0,0 +1,739 @@
+//! Free functions operating on slices of bits (`u1`s)
+//!
+//! Unless stated otherwise, bit slices are ordered least significant bit
+//! first, matching [`bits_of_u8`](crate::bits_of_u8).
+
+use std::{cmp::Ordering, fmt};
+
+use crate::u1::{b0, b1, u1};
+
+/// Returns the bit at `index`, or zero if `index` is past the end of `bits`
+fn bit_or_zero(bits: &[u1], index: usize) -> u1 {
+    bits.get(index).copied().unwrap_or(b0)
+}
+
+/// Returns true if `a` and `b` represent the same unsigned number.
+///
+/// Slices of different lengths are compared as if the shorter one were
+/// padded with zeros at its most significant end, so `[1]` equals `[1, 0]`.
+pub fn bits_eq(a: &[u1], b: &[u1]) -> bool {
+    bits_cmp(a, b) == Ordering::Equal
+}
+
+/// Compares `a` and `b` as unsigned numbers, starting from the most
+/// significant bit.
+///
+/// Slices of different lengths are compared as if the shorter one were
+/// padded with zeros at its most significant end.
+pub fn bits_cmp(a: &[u1], b: &[u1]) -> Ordering {
+    (0..a.len().max(b.len()))
+        .rev()
+        .map(|i| bit_or_zero(a, i).cmp(&bit_or_zero(b, i)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Reverses the order of the bytes in `bits` while keeping the order of the
+/// bits within each byte. `N` must be a multiple of 8.
+fn swap_bytes<const N: usize>(bits: [u1; N]) -> [u1; N] {
+    std::array::from_fn(|i| bits[(N / 8 - 1 - i / 8) * 8 + i % 8])
+}
+
+/// Reverses the byte order of a 16-bit value, like [`u16::swap_bytes`]
+pub fn swap_bytes_u16(bits: [u1; 16]) -> [u1; 16] {
+    swap_bytes(bits)
+}
+
+/// Reverses the byte order of a 32-bit value, like [`u32::swap_bytes`]
+pub fn swap_bytes_u32(bits: [u1; 32]) -> [u1; 32] {
+    swap_bytes(bits)
+}
+
+/// Returns the number of consecutive ones at the most significant end of
+/// `bits`, which is `bits.len()` if every bit is one
+pub fn leading_ones(bits: &[u1]) -> usize {
+    bits.iter().rev().take_while(|bit| bit.0).count()
+}
+
+/// Returns the number of consecutive ones at the least significant end of
+/// `bits`, which is `bits.len()` if every bit is one
+pub fn trailing_ones(bits: &[u1]) -> usize {
+    bits.iter().take_while(|bit| bit.0).count()
+}
+
+/// Sorts `bits` so every zero comes before every one, using a sorting
+/// network made only of compare-exchange gates.
+///
+/// Each gate puts `a & b` (the smaller bit) in the lower position and
+/// `a | b` (the larger bit) in the higher one. The network is an odd-even
+/// transposition sort: `bits.len()` rounds of gates between neighbours,
+/// alternating between even and odd pairs, which sorts any length.
+pub fn sort_bits(bits: &mut [u1]) {
+    for round in 0..bits.len() {
+        for low in (round % 2..bits.len().saturating_sub(1)).step_by(2) {
+            let (a, b) = (bits[low], bits[low + 1]);
+            bits[low] = a & b;
+            bits[low + 1] = a | b;
+        }
+    }
+}
+
+/// Returns the median of `window`, which for bits is the majority bit.
+///
+/// The window is sorted with the [`sort_bits`] network and the middle bit
+/// is taken. For an even length with as many ones as zeros, the upper of
+/// the two middle bits is taken, so ties give one.
+///
+/// # Panics
+/// Panics if `window` is empty
+pub fn median_filter_bits(window: &[u1]) -> u1 {
+    assert!(
+        !window.is_empty(),
+        "can't take the median of an empty window"
+    );
+    let mut sorted = window.to_vec();
+    sort_bits(&mut sorted);
+    sorted[sorted.len() / 2]
+}
+
+/// Returns `n` copies of `bit`
+pub fn repeat_bit(bit: u1, n: usize) -> Vec<u1> {
+    vec![bit; n]
+}
+
+/// Returns the AND of every bit, like Verilog's `&bits`.
+/// An empty slice gives one, the identity of AND.
+pub fn reduce_and(bits: &[u1]) -> u1 {
+    bits.iter().fold(b1, |acc, &bit| acc & bit)
+}
+
+/// Returns the OR of every bit, like Verilog's `|bits`.
+/// An empty slice gives zero, the identity of OR.
+pub fn reduce_or(bits: &[u1]) -> u1 {
+    bits.iter().fold(b0, |acc, &bit| acc | bit)
+}
+
+/// Returns the XOR of every bit, like Verilog's `^bits`, which is one when
+/// an odd number of bits are set. An empty slice gives zero, the identity
+/// of XOR.
+pub fn reduce_xor(bits: &[u1]) -> u1 {
+    bits.iter().fold(b0, |acc, &bit| acc ^ bit)
+}
+
+/// Run-length encodes `bits` as `(bit, run length)` pairs, in order.
+/// Neighbouring runs always hold different bits, and no run is empty.
+pub fn rle_encode(bits: &[u1]) -> Vec<(u1, usize)> {
+    bits.chunk_by(|a, b| a == b)
+        .map(|run| (run[0], run.len()))
+        .collect()
+}
+
+/// Expands runs produced by [`rle_encode`] back into bits
+pub fn rle_decode(runs: &[(u1, usize)]) -> Vec<u1> {
+    runs.iter()
+        .flat_map(|&(bit, len)| std::iter::repeat_n(bit, len))
+        .collect()
+}
+
+/// Which pair of bits Manchester coding sends for each data bit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Manchester {
+    /// IEEE 802.3: a zero is sent as `[1, 0]` and a one as `[0, 1]`
+    Ieee,
+
+    /// G. E. Thomas: a zero is sent as `[0, 1]` and a one as `[1, 0]`
+    Thomas,
+}
+
+impl Manchester {
+    /// Returns the pair of bits sent for `bit`
+    fn pair(self, bit: u1) -> [u1; 2] {
+        match (self, bit) {
+            (Self::Ieee, u1(false)) | (Self::Thomas, u1(true)) => [b1, b0],
+            _ => [b0, b1],
+        }
+    }
+}
+
+/// Manchester-encodes `bits`, sending each as two bits chosen by `convention`
+pub fn manchester_encode(bits: &[u1], convention: Manchester) -> Vec<u1> {
+    bits.iter().flat_map(|&bit| convention.pair(bit)).collect()
+}
+
+/// Decodes bits produced by [`manchester_encode`] with the same
+/// `convention`. Fails if there are an odd number of bits or a pair has
+/// no transition (`[0, 0]` or `[1, 1]`).
+pub fn manchester_decode(bits: &[u1], convention: Manchester) -> Result<Vec<u1>, ManchesterError> {
+    if !bits.len().is_multiple_of(2) {
+        return Err(ManchesterError::OddLength);
+    }
+    bits.chunks_exact(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            [b0, b1]
+                .into_iter()
+                .find(|&bit| convention.pair(bit) == pair)
+                .ok_or(ManchesterError::InvalidPair { index: 2 * index })
+        })
+        .collect()
+}
+
+/// The error returned by [`manchester_decode`] for a malformed bit stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManchesterError {
+    /// The number of bits isn't even, so they can't be split into pairs
+    OddLength,
+
+    /// The pair starting at `index` has both bits equal
+    InvalidPair {
+        /// Index of the first bit of the pair
+        index: usize,
+    },
+}
+
+impl fmt::Display for ManchesterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OddLength => write!(f, "Manchester code has an odd number of bits"),
+            Self::InvalidPair { index } => {
+                write!(f, "invalid Manchester pair at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManchesterError {}
+
+/// Adds `addend` and `carry` into `sum` starting at bit `offset`,
+/// ripple-carrying through the rest of `sum`.
+/// Returns the carry out of the top bit of `sum`.
+fn add_into(sum: &mut [u1], addend: &[u1], offset: usize, mut carry: u1) -> u1 {
+    for (i, bit) in sum.iter_mut().enumerate().skip(offset) {
+        (*bit, carry) = bit.carrying_add(bit_or_zero(addend, i - offset), carry);
+    }
+    carry
+}
+
+/// Adds `a`, `b` and `carry_in` with a ripple-carry adder, returning the sum
+/// and the carry out of the top bit.
+///
+/// The sum is as wide as the longer operand, and the shorter operand is
+/// zero-extended.
+pub fn add_bits(a: &[u1], b: &[u1], carry_in: u1) -> (Vec<u1>, u1) {
+    let mut sum: Vec<u1> = (0..a.len().max(b.len()))
+        .map(|i| bit_or_zero(a, i))
+        .collect();
+    let carry = add_into(&mut sum, b, 0, carry_in);
+    (sum, carry)
+}
+
+/// Subtracts `b` and `borrow_in` from `a` with a ripple-borrow subtractor,
+/// returning the difference and the borrow out of the top bit.
+///
+/// The difference is as wide as the longer operand, and the shorter operand
+/// is zero-extended. A borrow out of one means `a` was smaller than
+/// `b + borrow_in` and the difference has wrapped around.
+pub fn sub_bits(a: &[u1], b: &[u1], borrow_in: u1) -> (Vec<u1>, u1) {
+    let mut difference: Vec<u1> = (0..a.len().max(b.len()))
+        .map(|i| bit_or_zero(a, i))
+        .collect();
+    let mut borrow = borrow_in;
+    for (i, bit) in difference.iter_mut().enumerate() {
+        (*bit, borrow) = bit.borrowing_sub(bit_or_zero(b, i), borrow);
+    }
+    (difference, borrow)
+}
+
+/// Adds one to `bits` in place, returning true if it overflowed
+/// (wrapping from all ones to all zeros)
+pub fn increment(bits: &mut [u1]) -> bool {
+    add_into(bits, &[], 0, b1).0
+}
+
+/// Subtracts one from `bits` in place, returning true if it underflowed
+/// (wrapping from all zeros to all ones)
+pub fn decrement(bits: &mut [u1]) -> bool {
+    for bit in bits {
+        *bit = !*bit;
+        if !bit.0 {
+            return false;
+        }
+    }
+    true
+}
+
+/// Adds two fixed-width bit arrays and a carry with a ripple-carry adder,
+/// returning the sum and the carry out of the top bit.
+/// Unlike [`add_bits`], this doesn't allocate.
+pub fn ripple<const N: usize>(a: [u1; N], b: [u1; N], carry_in: u1) -> ([u1; N], u1) {
+    let mut sum = a;
+    let carry = add_into(&mut sum, &b, 0, carry_in);
+    (sum, carry)
+}
+
+/// Counts the ones in `bits` the way hardware does, with a tree of adders,
+/// and returns the count as a bit slice.
+///
+/// Every bit starts out as a 1-bit number. Each level of the tree adds
+/// neighbouring numbers in pairs, keeping the carry as a new top bit, until
+/// a single number is left. An empty slice gives an empty (zero) count.
+pub fn popcount_network(bits: &[u1]) -> Vec<u1> {
+    let mut level: Vec<Vec<u1>> = bits.iter().map(|bit| vec![*bit]).collect();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => {
+                    let (mut sum, carry) = add_bits(a, b, b0);
+                    sum.push(carry);
+                    sum
+                }
+                [a] => a.clone(),
+                _ => unreachable!("chunks of 2 have one or two elements"),
+            })
+            .collect();
+    }
+    level.pop().unwrap_or_default()
+}
+
+/// Adds `a` and `b` as unsigned numbers, returning an error if the sum
+/// doesn't fit in the width of the longer operand (the carry out is set)
+pub fn add_bits_checked(a: &[u1], b: &[u1]) -> Result<Vec<u1>, Overflow> {
+    match add_bits(a, b, b0) {
+        (sum, b0) => Ok(sum),
+        _ => Err(Overflow),
+    }
+}
+
+/// Widens a two's complement number to `to_len` bits by repeating its top
+/// bit. An empty slice is extended with zeros.
+///
+/// # Panics
+/// Panics if `to_len` is shorter than `bits`
+pub fn sign_extend(bits: &[u1], to_len: usize) -> Vec<u1> {
+    extend_with(bits, to_len, bits.last().copied().unwrap_or(b0))
+}
+
+/// Widens an unsigned number to `to_len` bits by adding zeros on top
+///
+/// # Panics
+/// Panics if `to_len` is shorter than `bits`
+pub fn zero_extend(bits: &[u1], to_len: usize) -> Vec<u1> {
+    extend_with(bits, to_len, b0)
+}
+
+/// Returns `bits` followed by copies of `fill` up to `to_len` bits
+fn extend_with(bits: &[u1], to_len: usize, fill: u1) -> Vec<u1> {
+    assert!(
+        to_len >= bits.len(),
+        "can't extend {} bits to {to_len} bits",
+        bits.len()
+    );
+    (0..to_len)
+        .map(|i| bits.get(i).copied().unwrap_or(fill))
+        .collect()
+}
+
+/// Adds `a` and `b` as two's complement numbers, returning an error if the
+/// sum doesn't fit in the width of the longer operand.
+///
+/// The shorter operand is sign-extended. Overflow happens when both operands
+/// have the same sign and the sign of the sum differs from it.
+pub fn add_bits_signed_checked(a: &[u1], b: &[u1]) -> Result<Vec<u1>, Overflow> {
+    let width = a.len().max(b.len());
+    let (a, b) = (sign_extend(a, width), sign_extend(b, width));
+    let (sum, _) = add_bits(&a, &b, b0);
+
+    match (a.last(), b.last(), sum.last()) {
+        (Some(a_sign), Some(b_sign), Some(sum_sign)) if a_sign == b_sign && sum_sign != a_sign => {
+            Err(Overflow)
+        }
+        _ => Ok(sum),
+    }
+}
+
+/// The error returned when the result of an operation doesn't fit in its bits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Multiplies `a` and `b` as unsigned numbers using shift-and-add.
+///
+/// The product is `a.len() + b.len()` bits long, which is always wide
+/// enough to hold it.
+pub fn mul_bits(a: &[u1], b: &[u1]) -> Vec<u1> {
+    let mut product = vec![b0; a.len() + b.len()];
+    for (shift, bit) in b.iter().enumerate() {
+        if bit.0 {
+            add_into(&mut product, a, shift, b0);
+        }
+    }
+    product
+}
+
+/// Returns the index of the highest set bit in `bits`,
+/// or `None` if every bit is zero
+pub fn priority_encode(bits: &[u1]) -> Option<usize> {
+    bits.iter().rposition(|bit| bit.0)
+}
+
+/// Renders `bits` most significant bit first, with a `_` between every
+/// `group` bits counting from the least significant end (like a Rust integer
+/// literal), e.g. `"1011_0010"` for eight bits grouped by 4.
+/// A `group` of 0 renders the bits without separators.
+pub fn debug_bits(bits: &[u1], group: usize) -> String {
+    let mut rendered = String::with_capacity(bits.len() * 2);
+    for (i, bit) in bits.iter().enumerate().rev() {
+        rendered.push_str(&bit.to_string());
+        if group != 0 && i != 0 && i % group == 0 {
+            rendered.push('_');
+        }
+    }
+    rendered
+}
+
+/// Wrapper for displaying a slice of bits, most significant bit first.
+///
+/// The alternate form (`{:#}`) adds a `0b` prefix, and the `width`, `fill`
+/// and alignment format specifiers apply to the whole rendered number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitSlice<'a>(pub &'a [u1]);
+
+impl fmt::Display for BitSlice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if f.alternate() { "0b" } else { "" };
+        f.pad(&format!("{prefix}{}", debug_bits(self.0, 0)))
+    }
+}
+
+#[test]
+fn bits_cmp_works() {
+    // 0b011 < 0b100
+    assert_eq!(bits_cmp(&[b1, b1, b0], &[b0, b0, b1]), Ordering::Less);
+    // 0b101 > 0b011
+    assert_eq!(bits_cmp(&[b1, b0, b1], &[b1, b1, b0]), Ordering::Greater);
+    assert_eq!(bits_cmp(&[b1, b0, b1], &[b1, b0, b1]), Ordering::Equal);
+    assert_eq!(bits_cmp(&[], &[]), Ordering::Equal);
+}
+
+#[test]
+fn bits_cmp_pads_shorter_slice() {
+    assert!(bits_eq(&[b1], &[b1, b0, b0]));
+    assert!(!bits_eq(&[b1], &[b1, b0, b1]));
+    assert_eq!(bits_cmp(&[b1, b1], &[b0, b0, b1]), Ordering::Less);
+    assert_eq!(bits_cmp(&[b0, b0, b0, b1], &[b1, b1]), Ordering::Greater);
+}
+
+// Returns the lowest `N` bits of `n`, for comparing against native integers in tests
+#[cfg(test)]
+fn to_bits<const N: usize>(n: u64) -> [u1; N] {
+    std::array::from_fn(|i| u1(n & 1 << i > 0))
+}
+
+// Returns the number represented by `bits`, for comparing against native integers in tests
+#[cfg(test)]
+fn from_bits(bits: &[u1]) -> u64 {
+    bits.iter()
+        .enumerate()
+        .map(|(i, bit)| u64::from(*bit) << i)
+        .sum()
+}
+
+#[test]
+fn swap_bytes_matches_native() {
+    for n in [0x0000_u16, 0x1234, 0xab01, 0xff00] {
+        let swapped = swap_bytes_u16(to_bits(n.into()));
+        assert_eq!(from_bits(&swapped), n.swap_bytes().into());
+    }
+    for n in [0x0000_0000_u32, 0x1234_5678, 0xdead_beef, 0x8000_0001] {
+        let swapped = swap_bytes_u32(to_bits(n.into()));
+        assert_eq!(from_bits(&swapped), n.swap_bytes().into());
+    }
+}
+
+#[test]
+fn debug_bits_works() {
+    let bits = [b0, b1, b0, b0, b1, b1, b0, b1];
+    assert_eq!(debug_bits(&bits, 4), "1011_0010");
+    assert_eq!(debug_bits(&bits, 3), "10_110_010");
+    assert_eq!(debug_bits(&bits, 0), "10110010");
+    assert_eq!(debug_bits(&bits, 8), "10110010");
+    assert_eq!(debug_bits(&[], 4), "");
+}
+
+#[test]
+fn bit_slice_display_works() {
+    let bits = BitSlice(&[b1, b0, b1, b1]);
+    assert_eq!(format!("{bits}"), "1101");
+    assert_eq!(format!("{bits:#}"), "0b1101");
+    assert_eq!(format!("{bits:>6}"), "  1101");
+    assert_eq!(format!("{bits:_<#8}"), "0b1101__");
+    assert_eq!(format!("{}", BitSlice(&[])), "");
+}
+
+#[test]
+fn mul_bits_matches_native() {
+    for (a, b) in [(0_u64, 0xffff), (3, 5), (0xffff, 0xffff), (0x1234, 0xbeef)] {
+        let product = mul_bits(&to_bits::<16>(a), &to_bits::<16>(b));
+        assert_eq!(product.len(), 32);
+        assert_eq!(from_bits(&product), a * b);
+    }
+
+    let product = mul_bits(&to_bits::<32>(0xdead_beef), &to_bits::<32>(0xffff_ffff));
+    assert_eq!(from_bits(&product), 0xdead_beef * 0xffff_ffff);
+    assert_eq!(mul_bits(&to_bits::<3>(5), &[]), [b0; 3]);
+}
+
+#[test]
+fn priority_encode_works() {
+    assert_eq!(priority_encode(&[b1, b0, b1, b0]), Some(2));
+    assert_eq!(priority_encode(&[b1, b0, b0, b0]), Some(0));
+    assert_eq!(priority_encode(&[b1; 9]), Some(8));
+    assert_eq!(priority_encode(&[b0; 4]), None);
+    assert_eq!(priority_encode(&[]), None);
+}
+
+#[test]
+fn repeat_bit_works() {
+    assert_eq!(repeat_bit(b1, 3), [b1, b1, b1]);
+    assert!(repeat_bit(b0, 0).is_empty());
+}
+
+#[test]
+fn leading_and_trailing_ones_work() {
+    let bits = [b0, b1, b1, b1]; // 0b1110
+    assert_eq!((leading_ones(&bits), trailing_ones(&bits)), (3, 0));
+    let bits = [b1, b1, b1, b0]; // 0b0111
+    assert_eq!((leading_ones(&bits), trailing_ones(&bits)), (0, 3));
+    assert_eq!((leading_ones(&[b1; 6]), trailing_ones(&[b1; 6])), (6, 6));
+    assert_eq!((leading_ones(&[]), trailing_ones(&[])), (0, 0));
+}
+
+#[test]
+fn add_bits_matches_native() {
+    for (a, b) in [
+        (0, 0),
+        (3, 5),
+        (0x00ff, 0x0001),
+        (0xffff, 0xffff),
+        (0x1234, 0xbeef),
+    ] {
+        let (sum, carry) = add_bits(&to_bits::<16>(a), &to_bits::<16>(b), b0);
+        assert_eq!(from_bits(&sum) | u64::from(carry) << 16, a + b);
+    }
+    let (sum, carry) = add_bits(&to_bits::<4>(0b1111), &to_bits::<2>(0), b1);
+    assert_eq!((from_bits(&sum), carry), (0, b1));
+}
+
+#[test]
+fn sub_bits_matches_native() {
+    for (a, b) in [(0, 0), (5, 3), (0xbeef, 0x1234), (3, 5), (0, 0xffff)] {
+        let (difference, borrow) = sub_bits(&to_bits::<16>(a), &to_bits::<16>(b), b0);
+        assert_eq!(from_bits(&difference), a.wrapping_sub(b) & 0xffff);
+        assert_eq!(borrow, u1(a < b));
+    }
+    let (difference, borrow) = sub_bits(&to_bits::<4>(0), &to_bits::<2>(0), b1);
+    assert_eq!((from_bits(&difference), borrow), (0b1111, b1));
+}
+
+#[test]
+fn add_bits_checked_detects_unsigned_carry() {
+    let sum = add_bits_checked(&to_bits::<4>(9), &to_bits::<4>(6));
+    assert_eq!(sum.map(|sum| from_bits(&sum)), Ok(15));
+    assert_eq!(
+        add_bits_checked(&to_bits::<4>(9), &to_bits::<4>(7)),
+        Err(Overflow)
+    );
+}
+
+#[test]
+fn add_bits_signed_checked_detects_overflow() {
+    // 5 + 2 = 7 fits, 5 + 3 = 8 doesn't
+    assert!(add_bits_signed_checked(&to_bits::<4>(5), &to_bits::<4>(2)).is_ok());
+    assert_eq!(
+        add_bits_signed_checked(&to_bits::<4>(5), &to_bits::<4>(3)),
+        Err(Overflow)
+    );
+    // -8 + -1 = -9 doesn't fit
+    assert_eq!(
+        add_bits_signed_checked(&to_bits::<4>(0b1000), &to_bits::<4>(0b1111)),
+        Err(Overflow)
+    );
+    // -1 + 1 = 0 carries out but doesn't overflow, and [1, 1] is sign-extended -1
+    let sum = add_bits_signed_checked(&to_bits::<4>(1), &to_bits::<2>(0b11));
+    assert_eq!(sum.map(|sum| from_bits(&sum)), Ok(0));
+}
+
+#[test]
+fn sort_bits_works() {
+    for n in 0..=u8::MAX {
+        let mut bits: [u1; 8] = to_bits(n.into());
+        sort_bits(&mut bits);
+        let ones = n.count_ones() as usize;
+        assert!(bits[..8 - ones].iter().all(|bit| !bit.0));
+        assert!(bits[8 - ones..].iter().all(|bit| bit.0));
+    }
+
+    let mut bits: [u1; 4] = to_bits(0b0101);
+    sort_bits(&mut bits);
+    assert_eq!(from_bits(&bits), 0b1100);
+
+    let mut bits: [u1; 1] = to_bits(1);
+    sort_bits(&mut bits);
+    sort_bits(&mut []);
+    assert_eq!(from_bits(&bits), 1);
+}
+
+#[test]
+fn ripple_matches_native() {
+    for (a, b) in [(0, 0), (7, 8), (9, 9), (15, 15)] {
+        let (sum, carry) = ripple(to_bits::<4>(a), to_bits::<4>(b), b0);
+        assert_eq!(from_bits(&sum) | u64::from(carry) << 4, a + b);
+    }
+    for (a, b) in [(0x1234, 0x4321), (0xffff, 1), (0xbeef, 0xdead)] {
+        let (sum, carry) = ripple(to_bits::<16>(a), to_bits::<16>(b), b1);
+        assert_eq!(from_bits(&sum) | u64::from(carry) << 16, a + b + 1);
+    }
+}
+
+#[test]
+fn popcount_network_matches_count_ones() {
+    for n in [
+        0_u64,
+        1,
+        0b1011,
+        0xff,
+        0x8000_0001,
+        0xdead_beef,
+        u32::MAX.into(),
+    ] {
+        let bits: [u1; 32] = to_bits(n);
+        assert_eq!(from_bits(&popcount_network(&bits)), n.count_ones().into());
+    }
+    let bits: [u1; 7] = to_bits(0b111_1111);
+    assert_eq!(from_bits(&popcount_network(&bits)), 7);
+    assert!(popcount_network(&[]).is_empty());
+}
+
+#[test]
+fn reductions_work() {
+    assert_eq!(reduce_and(&[]), b1);
+    assert_eq!(reduce_or(&[]), b0);
+    assert_eq!(reduce_xor(&[]), b0);
+
+    assert_eq!(reduce_and(&[b1, b1, b1]), b1);
+    assert_eq!(reduce_and(&[b1, b0, b1]), b0);
+    assert_eq!(reduce_or(&[b0, b0, b0]), b0);
+    assert_eq!(reduce_or(&[b0, b1, b0]), b1);
+    assert_eq!(reduce_xor(&[b1, b1, b0]), b0);
+    assert_eq!(reduce_xor(&[b1, b1, b1]), b1);
+}
+
+#[test]
+fn increment_and_decrement_work() {
+    let mut bits = to_bits::<4>(0b0111);
+    assert!(!increment(&mut bits));
+    assert_eq!(from_bits(&bits), 0b1000);
+    assert!(!decrement(&mut bits));
+    assert_eq!(from_bits(&bits), 0b0111);
+
+    let mut bits = [b1; 4];
+    assert!(increment(&mut bits));
+    assert_eq!(bits, [b0; 4]);
+    assert!(decrement(&mut bits));
+    assert_eq!(bits, [b1; 4]);
+
+    assert!(increment(&mut []));
+    assert!(decrement(&mut []));
+}
+
+#[test]
+fn rle_round_trip_works() {
+    let alternating: Vec<u1> = (0..7).map(|i| u1(i % 2 == 1)).collect();
+    let runs = rle_encode(&alternating);
+    assert_eq!(runs.len(), 7);
+    assert!(runs.iter().all(|&(_, len)| len == 1));
+    assert_eq!(rle_decode(&runs), alternating);
+
+    let long_runs = [vec![b1; 100], vec![b0; 3], vec![b1; 20]].concat();
+    let runs = rle_encode(&long_runs);
+    assert_eq!(runs, [(b1, 100), (b0, 3), (b1, 20)]);
+    assert_eq!(rle_decode(&runs), long_runs);
+
+    assert_eq!(rle_encode(&[]), []);
+    assert_eq!(rle_decode(&[]), []);
+}
+
+#[test]
+fn sign_and_zero_extend_work() {
+    let negative = to_bits::<4>(0b1010);
+    let positive = to_bits::<4>(0b0101);
+
+    assert_eq!(from_bits(&sign_extend(&negative, 8)), 0b1111_1010);
+    assert_eq!(from_bits(&sign_extend(&positive, 8)), 0b0000_0101);
+    assert_eq!(from_bits(&zero_extend(&negative, 8)), 0b0000_1010);
+    assert_eq!(from_bits(&zero_extend(&positive, 8)), 0b0000_0101);
+    assert_eq!(sign_extend(&negative, 4), negative);
+    assert_eq!(sign_extend(&[], 3), [b0; 3]);
+}
+
+#[test]
+#[should_panic]
+fn sign_extend_to_shorter_length_panics() {
+    sign_extend(&[b0; 4], 3);
+}
+
+#[test]
+fn manchester_round_trip_works() {
+    let data = [b1, b0, b0, b1];
+
+    let ieee = manchester_encode(&data, Manchester::Ieee);
+    assert_eq!(ieee, [b0, b1, b1, b0, b1, b0, b0, b1]);
+    let thomas = manchester_encode(&data, Manchester::Thomas);
+    assert_eq!(thomas, ieee.iter().map(|&bit| !bit).collect::<Vec<_>>());
+
+    assert_eq!(
+        manchester_decode(&ieee, Manchester::Ieee),
+        Ok(data.to_vec())
+    );
+    assert_eq!(
+        manchester_decode(&thomas, Manchester::Thomas),
+        Ok(data.to_vec())
+    );
+    assert_eq!(manchester_decode(&[], Manchester::Ieee), Ok(vec![]));
+}
+
+#[test]
+fn manchester_decode_detects_errors() {
+    assert_eq!(
+        manchester_decode(&[b0, b1, b1], Manchester::Ieee),
+        Err(ManchesterError::OddLength)
+    );
+    assert_eq!(
+        manchester_decode(&[b0, b1, b1, b1], Manchester::Ieee),
+        Err(ManchesterError::InvalidPair { index: 2 })
+    );
+    assert_eq!(
+        manchester_decode(&[b0, b0], Manchester::Thomas),
+        Err(ManchesterError::InvalidPair { index: 0 })
+    );
+}
+
+#[test]
+fn median_filter_bits_works() {
+    assert_eq!(median_filter_bits(&[b1]), b1);
+    assert_eq!(median_filter_bits(&[b0, b1, b0]), b0);
+    assert_eq!(median_filter_bits(&[b1, b0, b1]), b1);
+    assert_eq!(median_filter_bits(&[b1, b1, b0, b0, b1]), b1);
+    assert_eq!(median_filter_bits(&[b0, b0, b1, b0, b1]), b0);
+    // Ties go to one
+    assert_eq!(median_filter_bits(&[b0, b1]), b1);
+    assert_eq!(median_filter_bits(&[b0, b0, b1, b0]), b0);
+}
@@ -0,0 +1,219 @@
+//! Everything related to the BitVec struct
+
+use std::ops::Index;
+
+use crate::u1::{b0, b1, u1};
+
+/// A growable sequence of bits (`u1`s), packed eight to a byte.
+///
+/// Bit `i` lives in byte `i / 8` at position `i % 8`, so every byte is
+/// stored least significant bit first like [`bits_of_u8`](crate::bits_of_u8).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitVec {
+    /// Packed bits, any bits past `len` in the last byte are always zero
+    bytes: Vec<u8>,
+
+    /// Number of bits stored
+    len: usize,
+}
+
+impl BitVec {
+    /// Returns an empty BitVec
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a BitVec holding the first `bit_len` bits of `bytes`, packed
+    /// the same way as a BitVec. Padding bits past `bit_len` are cleared and
+    /// any extra bytes are ignored. Returns `None` if `bytes` holds fewer
+    /// than `bit_len` bits.
+    pub fn from_bytes_with_len(bytes: &[u8], bit_len: usize) -> Option<Self> {
+        let mut bytes = bytes.get(..bit_len.div_ceil(8))?.to_vec();
+        if let Some(last) = bytes.last_mut() {
+            if !bit_len.is_multiple_of(8) {
+                *last &= (1 << (bit_len % 8)) - 1;
+            }
+        }
+        Some(Self {
+            bytes,
+            len: bit_len,
+        })
+    }
+
+    /// Returns the number of bits in the BitVec
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the BitVec holds no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Appends a bit to the end of the BitVec
+    pub fn push(&mut self, bit: u1) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, bit);
+    }
+
+    /// Returns the bit at `index`, or `None` if it is out of bounds
+    pub fn get(&self, index: usize) -> Option<u1> {
+        (index < self.len).then(|| u1(self.bytes[index / 8] & 1 << (index % 8) > 0))
+    }
+
+    /// Returns a handle for reading and writing the bit at `index`,
+    /// or `None` if it is out of bounds
+    pub fn get_mut(&mut self, index: usize) -> Option<BitMut<'_>> {
+        (index < self.len).then_some(BitMut { vec: self, index })
+    }
+
+    /// Sets the bit at `index` to `bit`
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds
+    pub fn set(&mut self, index: usize, bit: u1) {
+        self.assert_in_bounds(index);
+        let mask = 1 << (index % 8);
+        if bit.0 {
+            self.bytes[index / 8] |= mask;
+        } else {
+            self.bytes[index / 8] &= !mask;
+        }
+    }
+
+    /// Flips the bit at `index`
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds
+    pub fn toggle(&mut self, index: usize) {
+        self.assert_in_bounds(index);
+        self.bytes[index / 8] ^= 1 << (index % 8);
+    }
+
+    fn assert_in_bounds(&self, index: usize) {
+        assert!(
+            index < self.len,
+            "index {index} out of bounds for BitVec of length {}",
+            self.len
+        );
+    }
+}
+
+impl Extend<u1> for BitVec {
+    fn extend<T: IntoIterator<Item = u1>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        let (min_len, _) = iter.size_hint();
+        self.bytes
+            .reserve((self.len + min_len).div_ceil(8) - self.bytes.len());
+
+        for bit in iter {
+            self.push(bit);
+        }
+    }
+}
+
+impl FromIterator<u1> for BitVec {
+    fn from_iter<T: IntoIterator<Item = u1>>(iter: T) -> Self {
+        let mut bits = Self::new();
+        bits.extend(iter);
+        bits
+    }
+}
+
+/// Indexing returns a reference to one of the constants [`b0`] and [`b1`],
+/// since packed bits can't be borrowed individually. Use [`BitVec::set`],
+/// [`BitVec::toggle`] or [`BitVec::get_mut`] to modify bits in place.
+impl Index<usize> for BitVec {
+    type Output = u1;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.assert_in_bounds(index);
+        match self.get(index) {
+            Some(b1) => &b1,
+            _ => &b0,
+        }
+    }
+}
+
+/// A mutable handle to a single bit of a [`BitVec`], returned by [`BitVec::get_mut`]
+#[derive(Debug)]
+pub struct BitMut<'a> {
+    vec: &'a mut BitVec,
+    index: usize,
+}
+
+impl BitMut<'_> {
+    /// Returns the current value of the bit
+    pub fn get(&self) -> u1 {
+        self.vec[self.index]
+    }
+
+    /// Sets the bit to `bit`
+    pub fn set(&mut self, bit: u1) {
+        self.vec.set(self.index, bit);
+    }
+
+    /// Flips the bit
+    pub fn toggle(&mut self) {
+        self.vec.toggle(self.index);
+    }
+}
+
+#[test]
+fn toggle_across_byte_boundary_works() {
+    let mut bits = BitVec::new();
+    (0..12).for_each(|_| bits.push(b0));
+
+    bits.toggle(7);
+    bits.toggle(8);
+    assert_eq!(bits.bytes, [0b1000_0000, 0b0000_0001]);
+    assert_eq!((bits[6], bits[7], bits[8], bits[9]), (b0, b1, b1, b0));
+
+    bits.toggle(7);
+    bits.get_mut(9).unwrap().toggle();
+    assert_eq!(bits.bytes, [0, 0b0000_0011]);
+}
+
+#[test]
+fn set_works() {
+    let mut bits = BitVec::new();
+    (0..10).for_each(|_| bits.push(b1));
+
+    bits.set(0, b0);
+    bits.get_mut(9).unwrap().set(b0);
+    assert_eq!(bits.bytes, [0b1111_1110, 0b0000_0001]);
+    assert_eq!(bits.get(10), None);
+    assert!(bits.get_mut(10).is_none());
+}
+
+#[test]
+fn extend_works() {
+    let mut bits: BitVec = [b1, b0, b1].into_iter().collect();
+    bits.extend((0..10).map(|i| u1(i % 3 == 0)));
+
+    assert_eq!(bits.len(), 13);
+    assert_eq!(bits.bytes, [0b0100_1101, 0b0001_0010]);
+    assert_eq!(bits.get(12), Some(b1));
+}
+
+#[test]
+fn from_bytes_with_len_works() {
+    let original: BitVec = (0..13).map(|i| u1(i % 3 != 1)).collect();
+    let mut bytes = original.bytes.clone();
+    bytes[1] |= 0b1110_0000;
+    bytes.push(0xff);
+
+    let bits = BitVec::from_bytes_with_len(&bytes, 13).unwrap();
+    assert_eq!(bits, original);
+    assert_eq!(bits.len(), 13);
+
+    assert_eq!(BitVec::from_bytes_with_len(&[0xff], 9), None);
+    assert_eq!(
+        BitVec::from_bytes_with_len(&[0xff], 8).unwrap().bytes,
+        [0xff]
+    );
+    assert_eq!(BitVec::from_bytes_with_len(&[], 0), Some(BitVec::new()));
+}
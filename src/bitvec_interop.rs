@@ -0,0 +1,97 @@
+//! Conversions to and from the `bitvec` crate, enabled by the `bitvec-interop` feature
+//!
+//! Bits are copied in order, so index 0 of a `bitvec` slice is the least
+//! significant bit of a [`Nibble`] and the first bit of a [`BitVec`].
+
+use std::fmt;
+
+use ::bitvec::{slice::BitSlice, vec};
+
+use crate::{bitvec::BitVec, nibble::Nibble, u1::u1};
+
+impl From<Nibble> for vec::BitVec {
+    fn from(value: Nibble) -> Self {
+        value.bits().into_iter().map(u1::get).collect()
+    }
+}
+
+impl TryFrom<&BitSlice> for Nibble {
+    type Error = LengthMismatch;
+
+    fn try_from(value: &BitSlice) -> Result<Self, Self::Error> {
+        let bits: [u1; 4] = value
+            .iter()
+            .map(|bit| u1(*bit))
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|_| LengthMismatch {
+                expected: 4,
+                found: value.len(),
+            })?;
+        Ok(Nibble::from_bits(bits))
+    }
+}
+
+impl From<&BitVec> for vec::BitVec {
+    fn from(value: &BitVec) -> Self {
+        (0..value.len()).map(|i| value[i].get()).collect()
+    }
+}
+
+impl From<&BitSlice> for BitVec {
+    fn from(value: &BitSlice) -> Self {
+        value.iter().map(|bit| u1(*bit)).collect()
+    }
+}
+
+/// The error returned when converting a `bitvec` slice of the wrong length
+/// into a fixed-width type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthMismatch {
+    /// Number of bits the target type holds
+    pub expected: usize,
+
+    /// Number of bits in the slice
+    pub found: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} bits but found {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for LengthMismatch {}
+
+#[test]
+fn nibble_round_trip_works() {
+    use ::bitvec::{bitvec, order::Lsb0};
+
+    let nibble = Nibble::new(0b1011).unwrap();
+    let bits = vec::BitVec::from(nibble);
+    assert_eq!(bits, bitvec![1, 1, 0, 1]);
+    assert_eq!(Nibble::try_from(bits.as_bitslice()), Ok(nibble));
+
+    let too_long = bitvec![1, 0, 1, 0, 1];
+    assert_eq!(
+        Nibble::try_from(too_long.as_bitslice()),
+        Err(LengthMismatch {
+            expected: 4,
+            found: 5
+        })
+    );
+}
+
+#[test]
+fn bitvec_round_trip_works() {
+    use ::bitvec::{bitvec, order::Lsb0};
+
+    let original = bitvec![1, 0, 0, 1, 1, 1, 0, 1, 0, 1];
+    let ours = BitVec::from(original.as_bitslice());
+    assert_eq!(ours.len(), 10);
+    assert_eq!(vec::BitVec::from(&ours), original);
+}
@@ -0,0 +1,114 @@
+//! Everything related to the Byte struct
+
+use std::fmt;
+
+use crate::u1::u1;
+
+/// A byte stored as its eight bits, least significant bit first
+/// (the same order as [`bits_of_u8`](crate::bits_of_u8))
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Byte(pub [u1; 8]);
+
+impl Byte {
+    /// Returns the bits of the byte as a string of `'0'`s and `'1'`s, most
+    /// significant bit first if `msb_first` is set and least significant
+    /// bit first otherwise
+    pub fn to_binary_string(self, msb_first: bool) -> String {
+        if msb_first {
+            crate::bits::debug_bits(&self.0, 0)
+        } else {
+            self.0.iter().map(u1::to_string).collect()
+        }
+    }
+
+    /// Returns the XOR of all eight bits, which is one when an odd number
+    /// of them are set
+    pub fn parity(self) -> u1 {
+        crate::bits::reduce_xor(&self.0)
+    }
+}
+
+impl From<u8> for Byte {
+    fn from(value: u8) -> Self {
+        Self(crate::bits_of_u8(value))
+    }
+}
+
+impl From<Byte> for u8 {
+    fn from(value: Byte) -> Self {
+        crate::u8_from_bits(value.0)
+    }
+}
+
+/// The array is least significant bit first, like [`Byte`]'s bits
+impl From<[bool; 8]> for Byte {
+    fn from(value: [bool; 8]) -> Self {
+        Self(value.map(u1))
+    }
+}
+
+/// The array is least significant bit first, like [`Byte`]'s bits
+impl From<Byte> for [bool; 8] {
+    fn from(value: Byte) -> Self {
+        value.0.map(u1::get)
+    }
+}
+
+/// Displays the byte in binary, most significant bit first
+impl fmt::Display for Byte {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&self.to_binary_string(true))
+    }
+}
+
+#[test]
+fn u8_round_trip_works() {
+    for n in [0, 1, 0x5a, 0x80, 0xff] {
+        let byte = Byte::from(n);
+        assert_eq!(byte.0, crate::bits_of_u8(n));
+        assert_eq!(u8::from(byte), n);
+    }
+}
+
+#[test]
+fn display_works() {
+    assert_eq!(Byte::from(0b1011_0010).to_string(), "10110010");
+    assert_eq!(Byte::from(1).to_string(), "00000001");
+}
+
+#[test]
+fn to_binary_string_works() {
+    let byte = Byte::from(0b0000_0111);
+    assert_eq!(byte.to_binary_string(true), "00000111");
+    assert_eq!(byte.to_binary_string(false), "11100000");
+}
+
+#[test]
+fn parity_works() {
+    use crate::u1::{b0, b1};
+
+    assert_eq!(Byte::from(0).parity(), b0);
+    assert_eq!(Byte::from(0b1000_0001).parity(), b0);
+    assert_eq!(Byte::from(0b1000_0000).parity(), b1);
+    assert_eq!(Byte::from(0b0111_0000).parity(), b1);
+}
+
+#[test]
+fn bool_array_round_trip_works() {
+    let bools = [true, false, false, false, false, false, false, true];
+    assert_eq!(u8::from(Byte::from(bools)), 0b1000_0001);
+    for n in [0, 1, 0x5a, 0x80, 0xff] {
+        let byte = Byte::from(n);
+        assert_eq!(Byte::from(<[bool; 8]>::from(byte)), byte);
+    }
+}
+
+#[test]
+fn display_pads_like_u1() {
+    use crate::u1::b1;
+
+    assert_eq!(format!("{:>10}", Byte::from(5)), "  00000101");
+    assert_eq!(format!("{:>3}", b1), "  1");
+    assert_eq!(format!("{:-<4}", b1), format!("{:-<4}", "1"));
+    assert_eq!(format!("{:-<9}", Byte::from(1)), "00000001-");
+}
@@ -1,14 +1,20 @@
 //! Everything related to the f3 struct
 
-use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+    sync::OnceLock,
+};
 
-use num_traits::{One, Zero};
+use num_traits::{FromPrimitive, One, ToPrimitive, Zero};
 
 use crate::u1::{b0, b1, u1};
 
 /// A 3-bit floating point number.
 /// The equation for the value of a float is (sign * mantissa * (2 ^ expontent))
-#[derive(Clone, Copy, PartialOrd, Debug)]
+#[derive(Clone, Copy)]
 pub struct f3 {
     /// Denotes whether the number is positive (0 means positive, 1 means negative)
     sign: u1,
@@ -38,13 +44,402 @@ impl f3 {
             mantissa,
         }
     }
+
+    /// Builds a float from its bits in canonical form.
+    /// Every bit pattern is already a distinct value except the two NaNs,
+    /// whose sign means nothing, so a negative NaN comes back as the
+    /// positive one.
+    pub const fn normalize(sign: u1, exponent: u1, mantissa: u1) -> Self {
+        match Self::new(sign, exponent, mantissa).to_variant() {
+            F3Value::NegNan => Self::NAN,
+            _ => Self::new(sign, exponent, mantissa),
+        }
+    }
+
+    /// Returns the bits of the float packed as `sign exponent mantissa`,
+    /// e.g. `NEG_ONE` (sign 1, exponent 0, mantissa 1) becomes `0b101`.
+    /// This is the index of the float in the operation tables.
+    pub const fn index(self) -> usize {
+        (self.sign.0 as usize) << 2 | (self.exponent.0 as usize) << 1 | self.mantissa.0 as usize
+    }
+
+    /// Inverse of [`f3::index`], only the lowest three bits of `index` are used
+    pub const fn from_index(index: usize) -> Self {
+        Self::new(
+            u1(index & 0b100 > 0),
+            u1(index & 0b010 > 0),
+            u1(index & 0b001 > 0),
+        )
+    }
+
+    /// Returns the bits of the float in `[sign, exponent, mantissa]` order
+    pub const fn to_bits(self) -> [u1; 3] {
+        [self.sign, self.exponent, self.mantissa]
+    }
+
+    const fn is_nan(self) -> bool {
+        matches!(self.to_variant(), F3Value::Nan | F3Value::NegNan)
+    }
+
+    /// Returns true if both floats have the same bits. Unlike `==`, this
+    /// is true for a NaN compared with itself.
+    pub const fn total_eq(self, other: Self) -> bool {
+        self.index() == other.index()
+    }
+
+    /// Returns true if the sign bit is zero, including for `ZERO` and NaN
+    pub const fn is_sign_positive(self) -> bool {
+        !self.sign.0
+    }
+
+    /// Returns true if the sign bit is one, including for `NEG_ZERO` and
+    /// the negative NaN
+    pub const fn is_sign_negative(self) -> bool {
+        self.sign.0
+    }
+
+    /// Parses a float from exactly three `'0'`/`'1'` characters in
+    /// sign, exponent, mantissa order, so `"101"` is `NEG_ONE`
+    pub fn from_binary_str(s: &str) -> Result<Self, ParseF3Error> {
+        let bits = s
+            .chars()
+            .enumerate()
+            .map(|(index, c)| match c {
+                '0' => Ok(b0),
+                '1' => Ok(b1),
+                found => Err(ParseF3Error::InvalidChar { index, found }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match bits[..] {
+            [sign, exponent, mantissa] => Ok(Self::new(sign, exponent, mantissa)),
+            _ => Err(ParseF3Error::InvalidLength(bits.len())),
+        }
+    }
+
+    /// Returns the table of `op(lhs, rhs)` for every pair of f3s, building
+    /// it in `cell` on first use
+    fn table(
+        cell: &'static OnceLock<[[Self; 8]; 8]>,
+        op: fn(f32, f32) -> f32,
+    ) -> &'static [[Self; 8]; 8] {
+        cell.get_or_init(|| {
+            std::array::from_fn(|lhs| {
+                std::array::from_fn(|rhs| {
+                    let lhs = f32::from(Self::from_index(lhs));
+                    let rhs = f32::from(Self::from_index(rhs));
+                    op(lhs, rhs).into()
+                })
+            })
+        })
+    }
+
+    /// Returns the result of `lhs + rhs` for every pair of f3s.
+    ///
+    /// Both dimensions are indexed by the bits of the operand read as a 3-bit
+    /// number in `sign exponent mantissa` order, so `ONE` is at index `0b001`
+    /// and `NEG_INFINITY` at index `0b110`. The table is built once, and the
+    /// arithmetic operators look their results up in it.
+    pub fn add_table() -> &'static [[Self; 8]; 8] {
+        static TABLE: OnceLock<[[f3; 8]; 8]> = OnceLock::new();
+        Self::table(&TABLE, |lhs, rhs| lhs + rhs)
+    }
+
+    /// Returns the result of `lhs - rhs` for every pair of f3s,
+    /// laid out like [`f3::add_table`]
+    pub fn sub_table() -> &'static [[Self; 8]; 8] {
+        static TABLE: OnceLock<[[f3; 8]; 8]> = OnceLock::new();
+        Self::table(&TABLE, |lhs, rhs| lhs - rhs)
+    }
+
+    /// Returns the result of `lhs * rhs` for every pair of f3s,
+    /// laid out like [`f3::add_table`]
+    pub fn mul_table() -> &'static [[Self; 8]; 8] {
+        static TABLE: OnceLock<[[f3; 8]; 8]> = OnceLock::new();
+        Self::table(&TABLE, |lhs, rhs| lhs * rhs)
+    }
+
+    /// Returns the result of `lhs / rhs` for every pair of f3s,
+    /// laid out like [`f3::add_table`]
+    pub fn div_table() -> &'static [[Self; 8]; 8] {
+        static TABLE: OnceLock<[[f3; 8]; 8]> = OnceLock::new();
+        Self::table(&TABLE, |lhs, rhs| lhs / rhs)
+    }
+
+    /// Returns the result of `lhs % rhs` for every pair of f3s,
+    /// laid out like [`f3::add_table`]
+    pub fn rem_table() -> &'static [[Self; 8]; 8] {
+        static TABLE: OnceLock<[[f3; 8]; 8]> = OnceLock::new();
+        Self::table(&TABLE, |lhs, rhs| lhs % rhs)
+    }
+
+    /// Computes `(self * a) + b`, converting back to an f3 only once at the end.
+    ///
+    /// The result is NaN if any operand is NaN, if the product is
+    /// zero times infinity, or if the product and `b` are opposite infinities.
+    /// Otherwise any infinite product or `b` makes the result infinite.
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        f32::from(self).mul_add(f32::from(a), f32::from(b)).into()
+    }
+
+    /// Returns the f3 that `value` is rounded to when converted:
+    ///
+    /// - `0.`, `-0.`, `1.`, `-1.` and the infinities map to their f3 counterparts
+    /// - anything above 1 becomes `INFINITY`, and anything below -1 becomes
+    ///   `NEG_INFINITY`
+    /// - everything else (NaNs and the non-zero values strictly between -1
+    ///   and 1) becomes a NaN with the same sign
+    pub fn bucket_of(value: f32) -> Self {
+        // INFINITY
+        if value == f32::INFINITY {
+            Self::INFINITY
+        }
+        // -INFINITY
+        else if value == f32::NEG_INFINITY {
+            Self::NEG_INFINITY
+        }
+        // 0
+        else if value == 0. && value.is_sign_positive() {
+            Self::ZERO
+        }
+        // -0
+        else if value == -0. {
+            Self::NEG_ZERO
+        }
+        // 1
+        else if value == 1. {
+            Self::ONE
+        }
+        // -1
+        else if value == -1. {
+            Self::NEG_ONE
+        }
+        // > +1 (INFINITY)
+        else if value > 1. {
+            Self::INFINITY
+        }
+        // < -1 (-INFINITY)
+        else if value < -1. {
+            Self::NEG_INFINITY
+        }
+        // NAN
+        else if value.is_sign_positive() {
+            Self::NAN
+        }
+        // -NAN
+        else {
+            Self::NEG_NAN
+        }
+    }
+
+    /// Converts the float to a fixed-point integer with `frac_bits`
+    /// fractional bits (Q format), so `ONE` becomes `1 << frac_bits`.
+    /// Infinities and values too large for an `i32` saturate to
+    /// `i32::MAX`/`i32::MIN`, and NaN becomes 0.
+    pub fn to_q(self, frac_bits: u32) -> i32 {
+        let one = 1_i64 << frac_bits.min(32);
+        let q = match self.to_variant() {
+            F3Value::Zero | F3Value::NegZero | F3Value::Nan | F3Value::NegNan => 0,
+            F3Value::One => one,
+            F3Value::NegOne => -one,
+            F3Value::Inf => i64::MAX,
+            F3Value::NegInf => i64::MIN,
+        };
+        q.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+    }
+
+    /// Adds two floats, returning `None` when the exact sum can't be
+    /// represented (such as `ONE + ONE`), where `+` would round it to
+    /// infinity. Sums that are infinite or NaN because of the operands are
+    /// returned as they are.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let sum = f32::from(self) + f32::from(rhs);
+        let result = Self::from(sum);
+        (sum.is_nan() || f32::from(result) == sum).then_some(result)
+    }
+
+    /// Returns the float as a `(numerator, denominator)` pair, or `None` for
+    /// infinities and NaNs. Both zeros give `(0, 1)`.
+    pub fn as_fraction(self) -> Option<(i32, i32)> {
+        match self.to_variant() {
+            F3Value::Zero | F3Value::NegZero => Some((0, 1)),
+            F3Value::One => Some((1, 1)),
+            F3Value::NegOne => Some((-1, 1)),
+            F3Value::Inf | F3Value::NegInf | F3Value::Nan | F3Value::NegNan => None,
+        }
+    }
+
+    /// Returns true if `other` rounds to this float, as in
+    /// [`f3::bucket_of`]. So `ONE` equals only `1.0`, `INFINITY` equals
+    /// every float above 1, and `ZERO` equals `0.0` but not `-0.0`.
+    /// Like `==` between f3s, NaN equals nothing, so neither do the floats
+    /// between -1 and 1 that round to it.
+    pub fn eq_f32(self, other: f32) -> bool {
+        self == Self::bucket_of(other)
+    }
+
+    /// Raises the float to an integer power. Every power of an f3 value is
+    /// another f3 value, so the result is exact and matches [`f32::powi`]:
+    ///
+    /// - anything to the power of 0 is `ONE`, including zeros and NaNs
+    /// - `ONE` stays `ONE`, and `NEG_ONE` is `ONE` for even and `NEG_ONE`
+    ///   for odd exponents
+    /// - zeros stay zero for positive exponents and become infinities for
+    ///   negative ones
+    /// - infinities stay infinite for positive exponents and become zeros
+    ///   for negative ones
+    /// - the sign of a zero or infinity carries over for odd exponents and
+    ///   is dropped for even ones
+    /// - otherwise NaN stays NaN
+    pub fn powi(self, exp: i32) -> Self {
+        f32::from(self).powi(exp).into()
+    }
+
+    /// Clamps the float to `[ZERO, ONE]`: every negative value, including
+    /// `NEG_ZERO`, becomes `ZERO` and `INFINITY` becomes `ONE`. NaNs stay NaN.
+    pub fn clamp01(self) -> Self {
+        match self.to_variant() {
+            F3Value::NegInf | F3Value::NegOne | F3Value::NegZero => Self::ZERO,
+            F3Value::Inf => Self::ONE,
+            _ => self,
+        }
+    }
+
+    /// Clamps the float to `[NEG_ONE, ONE]`, so the infinities become
+    /// `NEG_ONE` and `ONE`. NaNs stay NaN.
+    pub fn clamp_unit(self) -> Self {
+        match self.to_variant() {
+            F3Value::NegInf => Self::NEG_ONE,
+            F3Value::Inf => Self::ONE,
+            _ => self,
+        }
+    }
+
+    /// Returns a distinct index from 0 to 7 for each value, in value order:
+    /// `NEG_INFINITY` is 0, then `NEG_ONE`, `NEG_ZERO`, `ZERO`, `ONE` and
+    /// `INFINITY`, with `NEG_NAN` at 6 and `NAN` at 7. Sorting by it puts
+    /// the non-NaN values in order and the NaNs last.
+    pub const fn bucket_index(self) -> u8 {
+        match self.to_variant() {
+            F3Value::NegInf => 0,
+            F3Value::NegOne => 1,
+            F3Value::NegZero => 2,
+            F3Value::Zero => 3,
+            F3Value::One => 4,
+            F3Value::Inf => 5,
+            F3Value::NegNan => 6,
+            F3Value::Nan => 7,
+        }
+    }
+
+    /// Returns every value an f3 can hold, in the order of [`f3::index`]
+    pub fn representable_values() -> [Self; 8] {
+        std::array::from_fn(Self::from_index)
+    }
+
+    /// Returns which of the eight possible values the float holds
+    pub const fn to_variant(self) -> F3Value {
+        match self.index() {
+            0b000 => F3Value::Zero,
+            0b001 => F3Value::One,
+            0b010 => F3Value::Inf,
+            0b011 => F3Value::Nan,
+            0b100 => F3Value::NegZero,
+            0b101 => F3Value::NegOne,
+            0b110 => F3Value::NegInf,
+            _ => F3Value::NegNan,
+        }
+    }
+
+    /// Returns the float holding `value`
+    pub const fn from_variant(value: F3Value) -> Self {
+        match value {
+            F3Value::Zero => Self::ZERO,
+            F3Value::NegZero => Self::NEG_ZERO,
+            F3Value::One => Self::ONE,
+            F3Value::NegOne => Self::NEG_ONE,
+            F3Value::Inf => Self::INFINITY,
+            F3Value::NegInf => Self::NEG_INFINITY,
+            F3Value::Nan => Self::NAN,
+            F3Value::NegNan => Self::NEG_NAN,
+        }
+    }
+}
+
+/// Every value an [`f3`] can hold, for matching on exhaustively
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum F3Value {
+    /// Positive zero
+    Zero,
+
+    /// Negative zero
+    NegZero,
+
+    /// One
+    One,
+
+    /// Negative one
+    NegOne,
+
+    /// Positive infinity
+    Inf,
+
+    /// Negative infinity
+    NegInf,
+
+    /// Not a number, with the sign bit clear
+    Nan,
+
+    /// Not a number, with the sign bit set
+    NegNan,
+}
+
+impl From<f3> for F3Value {
+    fn from(value: f3) -> Self {
+        value.to_variant()
+    }
+}
+
+impl From<F3Value> for f3 {
+    fn from(value: F3Value) -> Self {
+        f3::from_variant(value)
+    }
+}
+
+/// The error returned by [`f3::from_binary_str`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseF3Error {
+    /// The string wasn't three characters long, holds the number of characters found
+    InvalidLength(usize),
+
+    /// The string contained a character other than `'0'` or `'1'`
+    InvalidChar {
+        /// Index (in chars) of the invalid character
+        index: usize,
+
+        /// The invalid character
+        found: char,
+    },
 }
 
+impl fmt::Display for ParseF3Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(len) => write!(f, "expected 3 binary digits but found {len}"),
+            Self::InvalidChar { index, found } => {
+                write!(f, "invalid binary digit {found:?} at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseF3Error {}
+
 impl Add for f3 {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        (f32::from(self) + f32::from(rhs)).into()
+        Self::add_table()[self.index()][rhs.index()]
     }
 }
 
@@ -52,7 +447,7 @@ impl Sub for f3 {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        (f32::from(self) - f32::from(rhs)).into()
+        Self::sub_table()[self.index()][rhs.index()]
     }
 }
 
@@ -60,7 +455,7 @@ impl Mul for f3 {
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        (f32::from(self) * f32::from(rhs)).into()
+        Self::mul_table()[self.index()][rhs.index()]
     }
 }
 
@@ -68,14 +463,14 @@ impl Div for f3 {
     type Output = Self;
 
     fn div(self, rhs: Self) -> Self::Output {
-        (f32::from(self) / f32::from(rhs)).into()
+        Self::div_table()[self.index()][rhs.index()]
     }
 }
 
 impl Rem for f3 {
     type Output = Self;
     fn rem(self, rhs: Self) -> Self::Output {
-        (f32::from(self) % f32::from(rhs)).into()
+        Self::rem_table()[self.index()][rhs.index()]
     }
 }
 
@@ -112,81 +507,665 @@ impl Neg for f3 {
 
 impl PartialEq for f3 {
     fn eq(&self, other: &Self) -> bool {
+        // Equal bit patterns are equal, except for NaNs which equal nothing
         [
-            [f3::ONE; 2],
-            [f3::NEG_ONE; 2],
-            [f3::ZERO; 2],
-            [f3::NEG_ZERO; 2],
-            [f3::INFINITY; 2],
-            [f3::NEG_INFINITY; 2],
+            f3::ONE,
+            f3::NEG_ONE,
+            f3::ZERO,
+            f3::NEG_ZERO,
+            f3::INFINITY,
+            f3::NEG_INFINITY,
         ]
-        .contains(&[*self, *other])
+        .map(f3::index)
+        .contains(&self.index())
+            && self.index() == other.index()
     }
 }
 
-impl From<f32> for f3 {
-    fn from(value: f32) -> Self {
-        // INFINITY
-        if value == f32::INFINITY {
-            Self::INFINITY
-        }
-        // -INFINITY
-        else if value == f32::NEG_INFINITY {
-            Self::NEG_INFINITY
-        }
-        // 0
-        else if value == 0. {
-            Self::ZERO
-        }
-        // -0
-        else if value == -0. {
-            Self::NEG_ZERO
-        }
-        // 1
-        else if value == 1. {
-            Self::ONE
-        }
-        // -1
-        else if value == -1. {
-            Self::NEG_ONE
+/// Compares with the f3 that the `f32` rounds to, see [`f3::eq_f32`]
+impl PartialEq<f32> for f3 {
+    fn eq(&self, other: &f32) -> bool {
+        self.eq_f32(*other)
+    }
+}
+
+/// Compares by value, returning `None` if either float is NaN.
+/// `NEG_ZERO` orders below `ZERO`, in line with `==` telling them apart.
+impl PartialOrd for f3 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (!self.is_nan() && !other.is_nan()).then(|| OrderedF3(*self).cmp(&OrderedF3(*other)))
+    }
+}
+
+impl f3 {
+    /// Returns the value as text, e.g. `-1` or `inf`
+    fn value_str(self) -> &'static str {
+        match self.to_variant() {
+            F3Value::Zero => "0",
+            F3Value::NegZero => "-0",
+            F3Value::One => "1",
+            F3Value::NegOne => "-1",
+            F3Value::Inf => "inf",
+            F3Value::NegInf => "-inf",
+            F3Value::Nan => "NaN",
+            F3Value::NegNan => "-NaN",
         }
-        // > +1 (INFINITY)
-        else if value > 1. {
-            Self::INFINITY
+    }
+}
+
+/// Shows the raw bits in `sign, exponent, mantissa` order followed by the
+/// value, e.g. `f3(1, 0, 1) = -1`
+impl fmt::Debug for f3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "f3({}, {}, {}) = {}",
+            self.sign,
+            self.exponent,
+            self.mantissa,
+            self.value_str()
+        )
+    }
+}
+
+/// Shows the value, e.g. `-1` or `inf`.
+///
+/// The alternate form (`{:#}`) instead shows how the bits fill in the
+/// formula `sign * mantissa * 2 ^ exponent`, e.g. `(-) 1 * 2^0` for
+/// `NEG_ONE`. Infinities and NaNs are special encodings whose value doesn't
+/// follow the formula, but their alternate form still shows their bits in it.
+impl fmt::Display for f3 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let sign = if self.sign.0 { '-' } else { '+' };
+            f.pad(&format!("({sign}) {} * 2^{}", self.mantissa, self.exponent))
+        } else {
+            f.pad(self.value_str())
         }
-        // < -1 (-INFINITY)
-        else if value < -1. {
-            Self::NEG_INFINITY
+    }
+}
+
+/// Integer conversions return `None` for infinities and NaNs
+impl ToPrimitive for f3 {
+    fn to_i64(&self) -> Option<i64> {
+        f32::from(*self).to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        f32::from(*self).to_u64()
+    }
+
+    fn to_f32(&self) -> Option<f32> {
+        Some(f32::from(*self))
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(f32::from(*self).into())
+    }
+}
+
+/// Conversions always succeed, rounding the same way as `From<f32>`
+impl FromPrimitive for f3 {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some((n as f32).into())
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some((n as f32).into())
+    }
+
+    fn from_f32(n: f32) -> Option<Self> {
+        Some(n.into())
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        Some((n as f32).into())
+    }
+}
+
+impl From<f32> for f3 {
+    fn from(value: f32) -> Self {
+        Self::bucket_of(value)
+    }
+}
+
+impl From<f3> for f32 {
+    fn from(val: f3) -> Self {
+        match val.to_variant() {
+            F3Value::Zero => 0.,
+            F3Value::NegZero => -0.,
+            F3Value::One => 1.,
+            F3Value::NegOne => -1.,
+            F3Value::Inf => f32::INFINITY,
+            F3Value::NegInf => f32::NEG_INFINITY,
+            F3Value::Nan => f32::NAN,
+            F3Value::NegNan => -f32::NAN,
         }
-        // NAN
-        else if value.is_sign_positive() {
-            Self::NAN
+    }
+}
+
+/// Saturating conversion: infinities become `i8::MAX` / `i8::MIN`,
+/// both zeros become 0 and NaNs become 0
+impl From<f3> for i8 {
+    fn from(val: f3) -> Self {
+        f32::from(val) as i8
+    }
+}
+
+/// Saturating conversion: `INFINITY` becomes `u8::MAX`, everything below
+/// zero (`NEG_ONE`, `NEG_INFINITY`) becomes 0 and NaNs become 0
+impl From<f3> for u8 {
+    fn from(val: f3) -> Self {
+        f32::from(val) as u8
+    }
+}
+
+/// Packs floats into bytes as densely as possible, 3 bits each, so 8
+/// floats fit in 3 bytes.
+///
+/// Float `i` occupies bits `3i..3i + 3` of the output, counting bits from the
+/// least significant bit of the first byte. Its bits are stored as
+/// [`f3::index`], mantissa lowest. Unused bits in the last byte are zero.
+pub fn pack_f3_slice(floats: &[f3]) -> Vec<u8> {
+    let mut bytes = vec![0; (floats.len() * 3).div_ceil(8)];
+    for (i, float) in floats.iter().enumerate() {
+        for bit in 0..3 {
+            if float.index() & 1 << bit > 0 {
+                let position = i * 3 + bit;
+                bytes[position / 8] |= 1 << (position % 8);
+            }
         }
-        // -NAN
-        else {
-            Self::NEG_NAN
+    }
+    bytes
+}
+
+/// Unpacks the first `count` floats from bytes written by [`pack_f3_slice`]
+///
+/// # Panics
+/// Panics if `bytes` holds fewer than `count` floats
+pub fn unpack_f3_slice(bytes: &[u8], count: usize) -> Vec<f3> {
+    assert!(
+        bytes.len() * 8 >= count * 3,
+        "{} bytes can't hold {count} packed f3s",
+        bytes.len()
+    );
+    (0..count)
+        .map(|i| {
+            f3::from_index((0..3).fold(0, |index, bit| {
+                let position = i * 3 + bit;
+                index | usize::from(bytes[position / 8] >> (position % 8) & 1) << bit
+            }))
+        })
+        .collect()
+}
+
+/// An f3 with a total order, so it can be sorted and used as a map key.
+///
+/// Values are ordered `NEG_INFINITY < NEG_ONE < NEG_ZERO < ZERO < ONE <
+/// INFINITY < NaN`. Both NaNs are equal to each other and greater than
+/// everything else. Zero and negative zero stay distinct, as they are for
+/// f3's own `PartialEq`.
+#[derive(Debug, Clone, Copy)]
+pub struct OrderedF3(pub f3);
+
+impl OrderedF3 {
+    /// Returns the position of the float in the total order
+    fn rank(self) -> u8 {
+        // Both NaNs share the last rank
+        self.0.bucket_index().min(6)
+    }
+}
+
+impl PartialEq for OrderedF3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank() == other.rank()
+    }
+}
+
+impl Eq for OrderedF3 {}
+
+impl Ord for OrderedF3 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for OrderedF3 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Hash for OrderedF3 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+    }
+}
+
+impl From<f3> for OrderedF3 {
+    fn from(value: f3) -> Self {
+        Self(value)
+    }
+}
+
+/// An f3 compared by its raw bits, so it can implement `Eq` and `Hash`,
+/// e.g. to be stored in a `HashSet`.
+///
+/// Unlike f3's own `PartialEq`, a NaN equals a NaN with the same sign bit.
+/// The two NaNs differ from each other, as do zero and negative zero.
+#[derive(Debug, Clone, Copy)]
+pub struct BitEqF3(pub f3);
+
+impl PartialEq for BitEqF3 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_eq(other.0)
+    }
+}
+
+impl Eq for BitEqF3 {}
+
+impl Hash for BitEqF3 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.index().hash(state);
+    }
+}
+
+impl From<f3> for BitEqF3 {
+    fn from(value: f3) -> Self {
+        Self(value)
+    }
+}
+
+#[test]
+fn operation_tables_are_symmetric() {
+    let add = f3::add_table();
+    let mul = f3::mul_table();
+    let not_nan = |i: &usize| ![f3::NAN, f3::NEG_NAN].map(f3::index).contains(i);
+
+    for lhs in (0..8).filter(not_nan) {
+        for rhs in (0..8).filter(not_nan) {
+            assert_eq!(add[lhs][rhs].index(), add[rhs][lhs].index());
+            assert_eq!(mul[lhs][rhs].index(), mul[rhs][lhs].index());
         }
     }
 }
 
-impl From<f3> for f32 {
-    fn from(val: f3) -> Self {
-        if val == f3::ZERO {
-            0.
-        } else if val == f3::NEG_ZERO {
-            -0.
-        } else if val == f3::ONE {
-            1.
-        } else if val == f3::NEG_ONE {
-            -1.
-        } else if val == f3::INFINITY {
-            f32::INFINITY
-        } else if val == f3::NEG_INFINITY {
-            f32::NEG_INFINITY
-        } else if val == f3::NEG_NAN {
-            -f32::NAN
-        } else {
-            f32::NAN
+#[test]
+fn eq_works() {
+    assert_eq!(f3::ONE, f3::ONE);
+    assert_eq!(f3::NEG_INFINITY, f3::NEG_INFINITY);
+    assert_ne!(f3::ONE, f3::NEG_ONE);
+    assert_ne!(f3::ZERO, f3::NEG_ZERO);
+    assert_ne!(f3::NAN, f3::NAN);
+    assert_ne!(f3::NEG_NAN, f3::NEG_NAN);
+}
+
+#[test]
+fn operation_tables_match_operators() {
+    let add = f3::add_table();
+    let div = f3::div_table();
+
+    assert_eq!(add[f3::ONE.index()][f3::NEG_ONE.index()], f3::ZERO);
+    assert_eq!(add[f3::ONE.index()][f3::ONE.index()], f3::INFINITY);
+    assert_eq!(div[f3::ONE.index()][f3::ZERO.index()], f3::ONE / f3::ZERO);
+}
+
+#[test]
+fn operators_match_f32_arithmetic() {
+    for lhs in f3::representable_values() {
+        for rhs in f3::representable_values() {
+            let (a, b) = (f32::from(lhs), f32::from(rhs));
+            assert_eq!((lhs + rhs).index(), f3::from(a + b).index());
+            assert_eq!((lhs - rhs).index(), f3::from(a - b).index());
+            assert_eq!((lhs * rhs).index(), f3::from(a * b).index());
+            assert_eq!((lhs / rhs).index(), f3::from(a / b).index());
+            assert_eq!((lhs % rhs).index(), f3::from(a % b).index());
         }
     }
 }
+
+#[test]
+fn primitive_conversions_work() {
+    assert_eq!(f3::from_f64(1.0), Some(f3::ONE));
+    assert_eq!(f3::from_i32(-1), Some(f3::NEG_ONE));
+    assert_eq!(f3::from_u8(7), Some(f3::INFINITY));
+    assert_eq!(f3::NEG_ONE.to_i8(), Some(-1));
+    assert_eq!(f3::NEG_ONE.to_u8(), None);
+    assert_eq!(f3::INFINITY.to_i32(), None);
+    assert_eq!(f3::NEG_INFINITY.to_f64(), Some(f64::NEG_INFINITY));
+}
+
+#[test]
+fn integer_conversions_saturate() {
+    let values = [
+        f3::ZERO,
+        f3::NEG_ZERO,
+        f3::ONE,
+        f3::NEG_ONE,
+        f3::INFINITY,
+        f3::NEG_INFINITY,
+        f3::NAN,
+        f3::NEG_NAN,
+    ];
+    assert_eq!(values.map(i8::from), [0, 0, 1, -1, i8::MAX, i8::MIN, 0, 0]);
+    assert_eq!(values.map(u8::from), [0, 0, 1, 0, u8::MAX, 0, 0, 0]);
+}
+
+#[test]
+fn mul_add_works() {
+    assert_eq!(f3::ONE.mul_add(f3::NEG_ONE, f3::ONE), f3::ZERO);
+    assert_eq!(f3::NEG_ONE.mul_add(f3::NEG_ONE, f3::ZERO), f3::ONE);
+    assert_eq!(f3::ONE.mul_add(f3::ONE, f3::ONE), f3::INFINITY);
+    assert_eq!(
+        f3::ZERO.mul_add(f3::ONE, f3::NEG_INFINITY),
+        f3::NEG_INFINITY
+    );
+    assert!(f32::from(f3::INFINITY.mul_add(f3::ZERO, f3::ONE)).is_nan());
+    assert!(f32::from(f3::INFINITY.mul_add(f3::ONE, f3::NEG_INFINITY)).is_nan());
+    assert!(f32::from(f3::ONE.mul_add(f3::ONE, f3::NAN)).is_nan());
+}
+
+#[test]
+fn from_binary_str_works() {
+    assert_eq!(f3::from_binary_str("010"), Ok(f3::INFINITY));
+    assert_eq!(f3::from_binary_str("101"), Ok(f3::NEG_ONE));
+    assert_eq!(f3::from_binary_str("011").unwrap().index(), f3::NAN.index());
+    assert_eq!(f3::NEG_INFINITY.to_bits(), [b1, b1, b0]);
+}
+
+#[test]
+fn from_binary_str_rejects_malformed_input() {
+    assert_eq!(
+        f3::from_binary_str("0101"),
+        Err(ParseF3Error::InvalidLength(4))
+    );
+    assert_eq!(f3::from_binary_str(""), Err(ParseF3Error::InvalidLength(0)));
+    assert_eq!(
+        f3::from_binary_str("0x1"),
+        Err(ParseF3Error::InvalidChar {
+            index: 1,
+            found: 'x'
+        })
+    );
+}
+
+#[test]
+fn ordered_f3_sorts_with_nan_last() {
+    let mut values = [
+        f3::NAN,
+        f3::ONE,
+        f3::NEG_INFINITY,
+        f3::ZERO,
+        f3::NEG_NAN,
+        f3::INFINITY,
+        f3::NEG_ZERO,
+        f3::NEG_ONE,
+    ]
+    .map(OrderedF3);
+    values.sort();
+
+    let sorted = values.map(|value| value.0.index());
+    assert_eq!(
+        sorted[..6],
+        [
+            f3::NEG_INFINITY,
+            f3::NEG_ONE,
+            f3::NEG_ZERO,
+            f3::ZERO,
+            f3::ONE,
+            f3::INFINITY
+        ]
+        .map(f3::index)
+    );
+    assert!(values[6..].iter().all(|value| f32::from(value.0).is_nan()));
+    assert_eq!(OrderedF3(f3::NAN), OrderedF3(f3::NEG_NAN));
+
+    let set: std::collections::HashSet<_> = values.into_iter().collect();
+    assert_eq!(set.len(), 7);
+}
+
+#[test]
+fn variants_are_a_bijection() {
+    let variants: Vec<F3Value> = (0..8).map(|i| f3::from_index(i).to_variant()).collect();
+    for (i, variant) in variants.iter().enumerate() {
+        assert_eq!(f3::from_variant(*variant).index(), i);
+        assert_eq!(variants.iter().filter(|other| *other == variant).count(), 1);
+    }
+    assert_eq!(f3::NEG_ONE.to_variant(), F3Value::NegOne);
+    assert_eq!(F3Value::from(f3::NAN), F3Value::Nan);
+}
+
+#[test]
+fn pack_f3_slice_round_trip_works() {
+    let floats: Vec<f3> = (0..8).chain([3, 5, 1]).map(f3::from_index).collect();
+    let packed = pack_f3_slice(&floats);
+    assert_eq!(packed.len(), 5);
+    assert_eq!(packed[..3], [0b1000_1000, 0b1100_0110, 0b1111_1010]);
+
+    let unpacked = unpack_f3_slice(&packed, floats.len());
+    assert_eq!(
+        unpacked
+            .iter()
+            .map(|float| float.index())
+            .collect::<Vec<_>>(),
+        floats.iter().map(|float| float.index()).collect::<Vec<_>>()
+    );
+    assert!(pack_f3_slice(&[]).is_empty());
+}
+
+#[test]
+fn signed_zero_and_nan_survive_f32_conversion() {
+    assert_eq!(f3::from(-0.).index(), f3::NEG_ZERO.index());
+    assert_eq!((f3::NEG_ONE * f3::ZERO).index(), f3::NEG_ZERO.index());
+    assert_ne!(f3::NEG_ONE * f3::ZERO, f3::ZERO);
+    assert!(f32::from(f3::NEG_NAN).is_sign_negative());
+}
+
+#[test]
+fn representable_values_round_trip_through_f32() {
+    for (i, value) in f3::representable_values().into_iter().enumerate() {
+        assert_eq!(value.index(), i);
+        assert_eq!(f3::bucket_of(f32::from(value)).index(), i);
+    }
+}
+
+#[test]
+fn bucket_of_works() {
+    assert_eq!(f3::bucket_of(-0.).index(), f3::NEG_ZERO.index());
+    assert_eq!(f3::bucket_of(2.5), f3::INFINITY);
+    assert_eq!(f3::bucket_of(-1.5), f3::NEG_INFINITY);
+    assert_eq!(f3::bucket_of(0.5).index(), f3::NAN.index());
+    assert_eq!(f3::bucket_of(-0.5).index(), f3::NEG_NAN.index());
+}
+
+#[test]
+fn normalize_works() {
+    assert_eq!(f3::normalize(b0, b0, b0).index(), f3::ZERO.index());
+    assert_eq!(f3::normalize(b1, b0, b0).index(), f3::NEG_ZERO.index());
+    assert_eq!(f3::normalize(b1, b1, b0).index(), f3::NEG_INFINITY.index());
+    assert_eq!(f3::normalize(b0, b1, b1).index(), f3::NAN.index());
+    assert_eq!(f3::normalize(b1, b1, b1).index(), f3::NAN.index());
+}
+
+#[test]
+fn to_q_works() {
+    assert_eq!(f3::ONE.to_q(0), 1);
+    assert_eq!(f3::ONE.to_q(8), 256);
+    assert_eq!(f3::NEG_ONE.to_q(8), -256);
+    assert_eq!(f3::ONE.to_q(31), i32::MAX);
+    assert_eq!(f3::NEG_ONE.to_q(31), i32::MIN);
+    assert_eq!(f3::INFINITY.to_q(8), i32::MAX);
+    assert_eq!(f3::NEG_INFINITY.to_q(8), i32::MIN);
+    assert_eq!(f3::NAN.to_q(8), 0);
+    assert_eq!(f3::NEG_ZERO.to_q(8), 0);
+}
+
+#[test]
+fn partial_cmp_works() {
+    assert!(f3::NEG_ONE < f3::ZERO);
+    assert!(f3::NEG_INFINITY < f3::NEG_ONE);
+    assert!(f3::NEG_ZERO < f3::ZERO);
+    assert!(f3::ONE < f3::INFINITY);
+    assert_eq!(f3::ONE.partial_cmp(&f3::ONE), Some(Ordering::Equal));
+    assert_eq!(f3::NAN.partial_cmp(&f3::ONE), None);
+    assert_eq!(f3::ZERO.partial_cmp(&f3::NEG_NAN), None);
+    assert_eq!(f3::NAN.partial_cmp(&f3::NAN), None);
+}
+
+#[test]
+fn sign_checks_work() {
+    assert!(f3::ZERO.is_sign_positive());
+    assert!(f3::NAN.is_sign_positive());
+    assert!(f3::NEG_ZERO.is_sign_negative());
+    assert!(f3::NEG_INFINITY.is_sign_negative());
+    assert!(!f3::ONE.is_sign_negative());
+}
+
+#[test]
+fn checked_add_works() {
+    assert!(f3::ONE.checked_add(f3::ONE).is_none());
+    assert!(f3::NEG_ONE.checked_add(f3::NEG_ONE).is_none());
+    assert_eq!(f3::ONE.checked_add(f3::NEG_ONE), Some(f3::ZERO));
+    assert_eq!(f3::ONE.checked_add(f3::ZERO), Some(f3::ONE));
+    assert_eq!(f3::INFINITY.checked_add(f3::ONE), Some(f3::INFINITY));
+    assert!(f3::INFINITY
+        .checked_add(f3::NEG_INFINITY)
+        .is_some_and(|sum| sum.is_nan()));
+    assert_eq!((f3::ONE + f3::ONE).index(), f3::INFINITY.index());
+}
+
+#[test]
+fn debug_works() {
+    assert_eq!(format!("{:?}", f3::NEG_ONE), "f3(1, 0, 1) = -1");
+    assert_eq!(format!("{:?}", f3::ZERO), "f3(0, 0, 0) = 0");
+    assert_eq!(format!("{:?}", f3::NEG_INFINITY), "f3(1, 1, 0) = -inf");
+    assert_eq!(format!("{:?}", f3::NAN), "f3(0, 1, 1) = NaN");
+    assert_eq!(format!("{:?}", Some(f3::ONE)), "Some(f3(0, 0, 1) = 1)");
+}
+
+#[test]
+fn bit_eq_f3_works() {
+    use std::collections::HashSet;
+
+    assert!(f3::NAN != f3::NAN);
+    assert!(f3::NAN.total_eq(f3::NAN));
+    assert_eq!(BitEqF3(f3::NAN), BitEqF3(f3::NAN));
+    assert_ne!(BitEqF3(f3::NAN), BitEqF3(f3::NEG_NAN));
+    assert_ne!(BitEqF3(f3::ZERO), BitEqF3(f3::NEG_ZERO));
+    assert_eq!(BitEqF3(f3::ONE), BitEqF3(f3::ONE));
+
+    let set: HashSet<BitEqF3> = [f3::NAN, f3::NAN, f3::ONE, f3::ZERO, f3::NEG_ZERO]
+        .into_iter()
+        .map(BitEqF3::from)
+        .collect();
+    assert_eq!(set.len(), 4);
+    assert!(set.contains(&BitEqF3(f3::NAN)));
+}
+
+#[test]
+fn as_fraction_works() {
+    assert_eq!(f3::ZERO.as_fraction(), Some((0, 1)));
+    assert_eq!(f3::NEG_ZERO.as_fraction(), Some((0, 1)));
+    assert_eq!(f3::ONE.as_fraction(), Some((1, 1)));
+    assert_eq!(f3::NEG_ONE.as_fraction(), Some((-1, 1)));
+    for value in [f3::INFINITY, f3::NEG_INFINITY, f3::NAN, f3::NEG_NAN] {
+        assert_eq!(value.as_fraction(), None);
+    }
+}
+
+#[test]
+fn eq_f32_works() {
+    assert!(f3::ONE == 1.0_f32);
+    assert!(f3::NEG_ONE == -1.0_f32);
+    assert!(f3::ZERO == 0.0_f32);
+    assert!(f3::ZERO != -0.0_f32);
+    assert!(f3::NEG_ZERO.eq_f32(-0.0));
+    assert!(f3::INFINITY == 2.0_f32);
+    assert!(f3::INFINITY == f32::INFINITY);
+    assert!(f3::NEG_INFINITY == -100.0_f32);
+    assert!(f3::ONE != 0.5_f32);
+    assert!(f3::NAN != 0.5_f32);
+    assert!(!f3::NAN.eq_f32(f32::NAN));
+}
+
+#[test]
+fn powi_works() {
+    let check = |base: f3, exp, expected: f3| {
+        assert!(
+            base.powi(exp).total_eq(expected),
+            "{base:?}.powi({exp}) should be {expected:?}, got {:?}",
+            base.powi(exp)
+        );
+    };
+
+    for value in f3::representable_values() {
+        check(value, 0, f3::ONE);
+    }
+    check(f3::ONE, 7, f3::ONE);
+    check(f3::ONE, -7, f3::ONE);
+    check(f3::NEG_ONE, 2, f3::ONE);
+    check(f3::NEG_ONE, -3, f3::NEG_ONE);
+    check(f3::ZERO, 3, f3::ZERO);
+    check(f3::ZERO, -1, f3::INFINITY);
+    check(f3::NEG_ZERO, 3, f3::NEG_ZERO);
+    check(f3::NEG_ZERO, 2, f3::ZERO);
+    check(f3::NEG_ZERO, -1, f3::NEG_INFINITY);
+    check(f3::INFINITY, 2, f3::INFINITY);
+    check(f3::INFINITY, -2, f3::ZERO);
+    check(f3::NEG_INFINITY, 3, f3::NEG_INFINITY);
+    check(f3::NEG_INFINITY, -1, f3::NEG_ZERO);
+    check(f3::NAN, 5, f3::NAN);
+}
+
+#[test]
+fn clamp_works() {
+    let check = |value: f3, expected01: f3, expected_unit: f3| {
+        assert!(value.clamp01().total_eq(expected01), "{value:?}.clamp01()");
+        assert!(
+            value.clamp_unit().total_eq(expected_unit),
+            "{value:?}.clamp_unit()"
+        );
+    };
+
+    check(f3::NEG_INFINITY, f3::ZERO, f3::NEG_ONE);
+    check(f3::NEG_ONE, f3::ZERO, f3::NEG_ONE);
+    check(f3::NEG_ZERO, f3::ZERO, f3::NEG_ZERO);
+    check(f3::ZERO, f3::ZERO, f3::ZERO);
+    check(f3::ONE, f3::ONE, f3::ONE);
+    check(f3::INFINITY, f3::ONE, f3::ONE);
+    check(f3::NAN, f3::NAN, f3::NAN);
+    check(f3::NEG_NAN, f3::NEG_NAN, f3::NEG_NAN);
+}
+
+#[test]
+fn display_works() {
+    assert_eq!(f3::NEG_ONE.to_string(), "-1");
+    assert_eq!(f3::INFINITY.to_string(), "inf");
+    assert_eq!(format!("{:>4}", f3::ONE), "   1");
+
+    assert_eq!(format!("{:#}", f3::ONE), "(+) 1 * 2^0");
+    assert_eq!(format!("{:#}", f3::NEG_ONE), "(-) 1 * 2^0");
+    assert_eq!(format!("{:#}", f3::NEG_ZERO), "(-) 0 * 2^0");
+    assert_eq!(format!("{:#}", f3::NAN), "(+) 1 * 2^1");
+    assert_eq!(format!("{:>#14}", f3::ONE), "   (+) 1 * 2^0");
+    assert_eq!(format!("{:*<#13}", f3::NEG_ONE), "(-) 1 * 2^0**");
+}
+
+#[test]
+fn bucket_index_works() {
+    let ordered = [
+        f3::NEG_INFINITY,
+        f3::NEG_ONE,
+        f3::NEG_ZERO,
+        f3::ZERO,
+        f3::ONE,
+        f3::INFINITY,
+    ];
+    let indices = ordered.map(f3::bucket_index);
+    assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(indices.iter().all(|&i| i < f3::NEG_NAN.bucket_index()));
+
+    let mut all: Vec<u8> = f3::representable_values().map(f3::bucket_index).to_vec();
+    all.sort();
+    assert_eq!(all, [0, 1, 2, 3, 4, 5, 6, 7]);
+}
@@ -0,0 +1,41 @@
+//! Conversions to and from the `half` crate's `f16`, enabled by the `half` feature
+//!
+//! Every f3 value is exactly representable as an `f16`, and every `f16` is
+//! exactly representable as an `f32`, so both directions go through `f32`
+//! and classify values the same way as [`f3::bucket_of`].
+
+use half::f16;
+
+use crate::f3::f3;
+
+impl From<f3> for f16 {
+    fn from(value: f3) -> Self {
+        f16::from_f32(value.into())
+    }
+}
+
+/// Rounds the same way as `From<f32> for f3`, see [`f3::bucket_of`]
+impl From<f16> for f3 {
+    fn from(value: f16) -> Self {
+        f3::bucket_of(value.to_f32())
+    }
+}
+
+#[test]
+fn f16_round_trip_works() {
+    for value in f3::representable_values() {
+        let half = f16::from(value);
+        assert_eq!(half.to_f32().to_bits(), f32::from(value).to_bits());
+        assert!(f3::from(half).total_eq(value));
+    }
+}
+
+#[test]
+fn f16_classification_matches_f32() {
+    for value in [0.5, -0.5, 2.0, -2.0, 65504.0, 1.0, -0.0] {
+        let half = f16::from_f32(value);
+        assert!(f3::from(half).total_eq(f3::from(value)));
+    }
+    assert!(f3::from(f16::MAX).total_eq(f3::from(f32::INFINITY)));
+    assert!(f3::from(f16::NAN).total_eq(f3::from(f32::NAN)));
+}
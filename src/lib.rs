@@ -3,9 +3,94 @@
 #![deny(missing_docs, clippy::needless_bool)]
 #![allow(non_upper_case_globals, non_camel_case_types)]
 
+#[cfg(feature = "bigint")]
+pub mod bigint_interop;
+pub mod bit_array;
+pub mod bit_indexed;
+pub mod bit_stream;
+pub mod bits;
+pub mod bitvec;
+#[cfg(feature = "bitvec-interop")]
+pub mod bitvec_interop;
+pub mod byte;
 pub mod f3;
+#[cfg(feature = "half")]
+pub mod half_interop;
+pub mod nibble;
+pub mod prefix_code;
 pub mod u1;
 
+/// The small number types in this crate ([`u1::u1`], [`nibble::Nibble`]
+/// and [`f3::f3`]), for code that wants to treat them uniformly
+pub trait SmallNum: Copy {
+    /// Returns the number of bits the type is made of
+    fn bit_width() -> u32;
+
+    /// Returns the value as a `u128`, or `None` if it isn't a non-negative
+    /// integer (which only happens for [`f3::f3`])
+    fn to_u128(self) -> Option<u128>;
+
+    /// Returns the type's zero
+    fn zero() -> Self;
+
+    /// Returns the type's one
+    fn one() -> Self;
+}
+
+impl SmallNum for u1::u1 {
+    fn bit_width() -> u32 {
+        1
+    }
+
+    fn to_u128(self) -> Option<u128> {
+        Some(self.into())
+    }
+
+    fn zero() -> Self {
+        u1::b0
+    }
+
+    fn one() -> Self {
+        u1::b1
+    }
+}
+
+impl SmallNum for nibble::Nibble {
+    fn bit_width() -> u32 {
+        4
+    }
+
+    fn to_u128(self) -> Option<u128> {
+        Some(self.get().into())
+    }
+
+    fn zero() -> Self {
+        Self::MIN
+    }
+
+    fn one() -> Self {
+        Self::new(1).unwrap()
+    }
+}
+
+impl SmallNum for f3::f3 {
+    fn bit_width() -> u32 {
+        3
+    }
+
+    fn to_u128(self) -> Option<u128> {
+        num_traits::ToPrimitive::to_u128(&self)
+    }
+
+    fn zero() -> Self {
+        num_traits::Zero::zero()
+    }
+
+    fn one() -> Self {
+        num_traits::One::one()
+    }
+}
+
 /// Returns the bits that make up a `u8`
 pub fn bits_of_u8(n: u8) -> [u1::u1; 8] {
     std::array::from_fn(|i| u1::u1(n & 1 << i > 0))
@@ -20,6 +105,99 @@ pub fn u8_from_bits(bits: [u1::u1; 8]) -> u8 {
         .sum()
 }
 
+/// Returns an iterator over the bits of `value` whose positions lie in
+/// `range`, least significant bit first
+///
+/// # Panics
+/// Panics if `range` reaches past bit 31
+pub fn bit_range_iter(value: u32, range: std::ops::Range<u32>) -> impl Iterator<Item = u1::u1> {
+    assert!(
+        range.end <= u32::BITS,
+        "bit range {range:?} is out of bounds for a u32"
+    );
+    range.map(move |i| u1::u1(value & 1 << i > 0))
+}
+
+/// Returns the bits of `value` whose positions lie in `range`,
+/// least significant bit first
+///
+/// # Panics
+/// Panics if `range` reaches past bit 31
+pub fn extract_bits(value: u32, range: std::ops::Range<u32>) -> Vec<u1::u1> {
+    bit_range_iter(value, range).collect()
+}
+
+/// Reverses the order of the low `bits` bits of `n`, dropping the rest,
+/// so `bit_reverse_permutation(0b001, 3)` is `0b100`
+///
+/// # Panics
+/// Panics if `bits` is greater than 32
+pub fn bit_reverse_permutation(n: u32, bits: u32) -> u32 {
+    bit_range_iter(n, 0..bits).fold(0, |reversed, bit| reversed << 1 | u32::from(bit))
+}
+
+/// Returns the bit-reversal permutation of `0..2^bits`, the order an
+/// in-place radix-2 FFT of `2^bits` points reads its input in
+///
+/// # Panics
+/// Panics if `bits` is 32 or more
+pub fn bit_reverse_table(bits: u32) -> Vec<u32> {
+    assert!(bits < u32::BITS, "a table of 2^{bits} entries is too large");
+    (0..1 << bits)
+        .map(|n| bit_reverse_permutation(n, bits))
+        .collect()
+}
+
+/// Swaps the high and low nibbles of a byte, so `0xAB` becomes `0xBA`
+pub fn swap_nibbles(n: u8) -> u8 {
+    let bits = bits_of_u8(n);
+    u8_from_bits(std::array::from_fn(|i| bits[(i + 4) % 8]))
+}
+
+/// Computes the CRC-8 of `data` with a bit-serial shift register.
+///
+/// Each byte is fed in most significant bit first. Whenever the bit shifted
+/// out of the register differs from the incoming data bit, the register is
+/// XORed with `polynomial` (given without its implicit top bit, e.g. `0x07`
+/// for x^8 + x^2 + x + 1). The register starts out as `initial`, and the
+/// result is neither reflected nor XORed with a final value.
+pub fn crc8(data: &[u8], polynomial: u8, initial: u8) -> u8 {
+    let polynomial = bits_of_u8(polynomial);
+    let mut register = bits_of_u8(initial);
+    for bit in data
+        .iter()
+        .flat_map(|&byte| bits_of_u8(byte).into_iter().rev())
+    {
+        let feedback = register[7] ^ bit;
+        register = std::array::from_fn(|i| {
+            let shifted = if i == 0 { u1::b0 } else { register[i - 1] };
+            shifted ^ (feedback & polynomial[i])
+        });
+    }
+    u8_from_bits(register)
+}
+
+/// Splits bytes into their eight bit planes: plane `i` holds bit `i` of
+/// every byte, in the same order as `data`
+pub fn bit_planes(data: &[u8]) -> [Vec<u1::u1>; 8] {
+    std::array::from_fn(|i| data.iter().map(|&byte| bits_of_u8(byte)[i]).collect())
+}
+
+/// Rebuilds the bytes split apart by [`bit_planes`]
+///
+/// # Panics
+/// Panics if the planes aren't all the same length
+pub fn from_bit_planes(planes: &[Vec<u1::u1>; 8]) -> Vec<u8> {
+    let len = planes[0].len();
+    assert!(
+        planes.iter().all(|plane| plane.len() == len),
+        "bit planes must all be the same length"
+    );
+    (0..len)
+        .map(|j| u8_from_bits(std::array::from_fn(|i| planes[i][j])))
+        .collect()
+}
+
 // Returns `num` with its `n`'th bit set to one
 fn set_nth_bit(num: u8, n: u8) -> u8 {
     num | 1 << n
@@ -27,5 +205,77 @@ fn set_nth_bit(num: u8, n: u8) -> u8 {
 
 #[test]
 fn set_nth_bit_works() {
-    assert_eq!(set_nth_bit(0, 7), 255);
+    assert_eq!(set_nth_bit(0, 7), 128);
+}
+
+#[test]
+fn bit_range_iter_works() {
+    use u1::{b0, b1};
+
+    let value = 0b1011_0110;
+    assert_eq!(
+        bit_range_iter(value, 2..6).collect::<Vec<_>>(),
+        extract_bits(value, 2..6)
+    );
+    assert_eq!(extract_bits(value, 2..6), [b1, b0, b1, b1]);
+    assert_eq!(bit_range_iter(value, 3..3).count(), 0);
+    assert_eq!(bit_range_iter(u32::MAX, 31..32).next(), Some(b1));
+}
+
+#[test]
+fn swap_nibbles_works() {
+    assert_eq!(swap_nibbles(0xAB), 0xBA);
+    assert_eq!(swap_nibbles(0x0F), 0xF0);
+    assert_eq!(swap_nibbles(0x00), 0x00);
+}
+
+#[test]
+fn crc8_matches_known_vectors() {
+    // CRC-8/SMBUS
+    assert_eq!(crc8(b"123456789", 0x07, 0x00), 0xF4);
+    // CRC-8/CDMA2000
+    assert_eq!(crc8(b"123456789", 0x9B, 0xFF), 0xDA);
+    assert_eq!(crc8(&[], 0x07, 0x5A), 0x5A);
+}
+
+#[test]
+fn small_num_works() {
+    fn check<T: SmallNum>(width: u32) {
+        assert_eq!(T::bit_width(), width);
+        assert_eq!(T::zero().to_u128(), Some(0));
+        assert_eq!(T::one().to_u128(), Some(1));
+    }
+
+    check::<u1::u1>(1);
+    check::<nibble::Nibble>(4);
+    check::<f3::f3>(3);
+    assert_eq!(SmallNum::to_u128(-<f3::f3 as SmallNum>::one()), None);
+}
+
+#[test]
+fn bit_planes_round_trip_works() {
+    use u1::{b0, b1};
+
+    let data = [0b0000_0001, 0b1000_0011, 0xff, 0];
+    let planes = bit_planes(&data);
+    assert_eq!(planes[0], [b1, b1, b1, b0]);
+    assert_eq!(planes[1], [b0, b1, b1, b0]);
+    assert_eq!(planes[7], [b0, b1, b1, b0]);
+    assert_eq!(from_bit_planes(&planes), data);
+    assert_eq!(from_bit_planes(&bit_planes(&[])), []);
+}
+
+#[test]
+fn bit_reverse_permutation_works() {
+    assert_eq!(bit_reverse_permutation(0b001, 3), 0b100);
+    assert_eq!(bit_reverse_permutation(0b1_0110, 4), 0b0110);
+    assert_eq!(bit_reverse_permutation(1, 32), 1 << 31);
+    assert_eq!(bit_reverse_permutation(5, 0), 0);
+
+    assert_eq!(bit_reverse_table(3), [0, 4, 2, 6, 1, 5, 3, 7]);
+    assert_eq!(
+        bit_reverse_table(4),
+        [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15]
+    );
+    assert_eq!(bit_reverse_table(0), [0]);
 }
@@ -0,0 +1,1635 @@
+//! Everything related to the Nibble struct
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    ops::{Add, BitAnd, BitOr, BitXor, Not, Sub},
+};
+
+use num_traits::{FromPrimitive, ToPrimitive};
+
+use crate::u1::{b0, u1};
+
+/// Unsigned 4-bit Integer (half a byte)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Nibble(u8);
+
+impl Nibble {
+    /// The smallest value a Nibble can hold (0)
+    pub const MIN: Self = Self(0);
+
+    /// The largest value a Nibble can hold (15)
+    pub const MAX: Self = Self(0b1111);
+
+    /// Returns a Nibble holding `n`, or `None` if `n` doesn't fit in four bits
+    pub const fn new(n: u8) -> Option<Self> {
+        if n <= Self::MAX.0 {
+            Some(Self(n))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the value of the Nibble as a `u8` (always in `0..=15`)
+    pub const fn get(self) -> u8 {
+        self.0
+    }
+
+    /// Parses a hexadecimal digit (`'0'..='9'`, `'a'..='f'` or `'A'..='F'`),
+    /// returning `None` for any other character
+    pub fn from_digit(c: char) -> Option<Self> {
+        c.to_digit(16).map(|digit| Self(digit as u8))
+    }
+
+    /// Returns the Nibble as a hexadecimal digit, using `'A'..='F'` above 9
+    /// if `uppercase` is set and `'a'..='f'` otherwise
+    pub fn to_digit(self, uppercase: bool) -> char {
+        let digit = char::from_digit(self.0.into(), 16).expect("a Nibble is always below 16");
+        if uppercase {
+            digit.to_ascii_uppercase()
+        } else {
+            digit
+        }
+    }
+
+    /// Returns the Nibble as a single digit in base `radix` (lowercase above
+    /// 9), or `None` if `radix` isn't between 2 and 16 or the Nibble is too
+    /// large to be one digit in it
+    pub fn to_char_radix(self, radix: u32) -> Option<char> {
+        if !(2..=16).contains(&radix) {
+            return None;
+        }
+        char::from_digit(self.0.into(), radix)
+    }
+
+    /// Parses a single digit in base `radix`, returning `None` if `radix`
+    /// isn't between 2 and 16 or `c` isn't a digit in it
+    pub fn from_char_radix(c: char, radix: u32) -> Option<Self> {
+        if !(2..=16).contains(&radix) {
+            return None;
+        }
+        c.to_digit(radix).map(|digit| Self(digit as u8))
+    }
+
+    /// Returns the bits that make up the Nibble, least significant bit first
+    pub fn bits(self) -> [u1; 4] {
+        std::array::from_fn(|i| u1(self.0 & 1 << i > 0))
+    }
+
+    /// Returns `(position, bit)` pairs, least significant bit first.
+    /// The bit at `position` carries a weight of `2^position`.
+    pub fn indexed_bits(self) -> impl Iterator<Item = (u32, u1)> {
+        (0..).zip(self.bits())
+    }
+
+    /// Returns a Nibble constructed from bits, least significant bit first
+    pub fn from_bits(bits: [u1; 4]) -> Self {
+        Self(
+            bits.into_iter()
+                .enumerate()
+                .filter(|(_, bit)| bit.0)
+                .fold(0, |n, (i, _)| crate::set_nth_bit(n, i as u8)),
+        )
+    }
+
+    /// Returns the bits of the Nibble as a string of `'0'`s and `'1'`s, most
+    /// significant bit first if `msb_first` is set and least significant
+    /// bit first otherwise, so 1 is `"0001"` or `"1000"` respectively
+    pub fn to_binary_string(self, msb_first: bool) -> String {
+        let bits = self.bits();
+        if msb_first {
+            crate::bits::debug_bits(&bits, 0)
+        } else {
+            bits.iter().map(u1::to_string).collect()
+        }
+    }
+
+    /// Returns a Nibble with all four bits set to `bit`
+    pub fn splat(bit: u1) -> Self {
+        Self::from_bits([bit; 4])
+    }
+
+    /// Returns the XOR of all four bits, which is one when an odd number
+    /// of them are set
+    pub fn parity(self) -> u1 {
+        crate::bits::reduce_xor(&self.bits())
+    }
+
+    /// Packs the Nibble and a parity bit into a 5-bit code word, with the
+    /// Nibble in bits 0 to 3 and the parity bit in bit 4. The parity bit is
+    /// chosen so the number of ones in the code word is even or odd, as
+    /// `mode` asks.
+    pub fn with_parity(self, mode: Parity) -> u8 {
+        self.0 | u8::from(mode.bit_for(self)) << 4
+    }
+
+    /// Checks a code word made by [`Nibble::with_parity`] with the same
+    /// `mode`, returning the Nibble without its parity bit
+    pub fn check_parity(code: u8, mode: Parity) -> Result<Self, ParityError> {
+        if code > 0b1_1111 {
+            return Err(ParityError::TooWide(code));
+        }
+        let n = Self(code & 0xF);
+        if n.with_parity(mode) == code {
+            Ok(n)
+        } else {
+            Err(ParityError::Mismatch)
+        }
+    }
+
+    /// Returns the number of consecutive ones starting from the most
+    /// significant bit, which is 4 if every bit is one
+    pub fn leading_ones(self) -> u32 {
+        crate::bits::leading_ones(&self.bits()) as u32
+    }
+
+    /// Returns the number of consecutive ones starting from the least
+    /// significant bit, which is 4 if every bit is one
+    pub fn trailing_ones(self) -> u32 {
+        crate::bits::trailing_ones(&self.bits()) as u32
+    }
+
+    /// Returns the position of the highest set bit,
+    /// or `None` if the Nibble is zero
+    pub fn highest_set_bit(self) -> Option<u32> {
+        crate::bits::priority_encode(&self.bits()).map(|i| i as u32)
+    }
+
+    /// Swaps the low pair of bits (0 and 1) with the high pair (2 and 3),
+    /// so `0b1101` becomes `0b0111`
+    pub fn swap_halves(self) -> Self {
+        let [bit0, bit1, bit2, bit3] = self.bits();
+        Self::from_bits([bit2, bit3, bit0, bit1])
+    }
+
+    /// Shifts the Nibble left by `n` places, dropping the bits shifted out,
+    /// or returns `None` if `n` is 4 or more
+    pub fn checked_shl(self, n: u32) -> Option<Self> {
+        (n < 4).then(|| Self((self.0 << n) & 0xF))
+    }
+
+    /// Shifts the Nibble left by `n % 4` places, dropping the bits shifted out
+    pub fn wrapping_shl(self, n: u32) -> Self {
+        Self((self.0 << (n % 4)) & 0xF)
+    }
+
+    /// Shifts the Nibble right by `n` places, filling the top with copies of
+    /// the sign bit, like `>>` on a signed integer when the Nibble is read as
+    /// two's complement. A logical shift (such as [`AluOp::Shr`]) fills with
+    /// zeros instead. Shifting by 4 or more leaves only copies of the sign bit.
+    pub fn arithmetic_shr(self, n: u32) -> Self {
+        let bits = self.bits();
+        let n = n.min(4) as usize;
+        Self::from_bits(std::array::from_fn(|i| {
+            bits.get(i + n).copied().unwrap_or(self.sign())
+        }))
+    }
+
+    /// Adds `rhs` to the Nibble, returning 15 instead of wrapping on a carry
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        match crate::bits::ripple(self.bits(), rhs.bits(), b0) {
+            (_, u1(true)) => Self::MAX,
+            (sum, _) => Self::from_bits(sum),
+        }
+    }
+
+    /// Subtracts `rhs` from the Nibble, returning 0 instead of wrapping on a
+    /// borrow
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        let (difference, flags) = self.sub_with_flags(rhs);
+        if flags.carry.0 {
+            Self::MIN
+        } else {
+            difference
+        }
+    }
+
+    /// Multiply-accumulate: computes `self + a * b` with a shift-and-add
+    /// multiplier and a ripple-carry adder. Returns the full result, which
+    /// is at most 240 and always fits in a `u8`, and whether it overflowed
+    /// a Nibble (is above 15).
+    pub fn mac(self, a: Self, b: Self) -> (u8, bool) {
+        let product = crate::bits::mul_bits(&a.bits(), &b.bits());
+        let sum: [u1; 8] = crate::bits::add_bits(&product, &self.bits(), b0)
+            .0
+            .try_into()
+            .expect("a product of two Nibbles is 8 bits");
+        (
+            crate::u8_from_bits(sum),
+            crate::bits::reduce_or(&sum[4..]).0,
+        )
+    }
+
+    /// Rotates the Nibble left through a carry bit, treating the two as a
+    /// single 5-bit value: every bit moves up one place, `carry_in` becomes
+    /// bit 0, and the old bit 3 is returned as the new carry
+    pub fn rcl(self, carry_in: u1) -> (Self, u1) {
+        let [bit0, bit1, bit2, bit3] = self.bits();
+        (Self::from_bits([carry_in, bit0, bit1, bit2]), bit3)
+    }
+
+    /// Rotates the Nibble right through a carry bit, treating the two as a
+    /// single 5-bit value: every bit moves down one place, `carry_in` becomes
+    /// bit 3, and the old bit 0 is returned as the new carry
+    pub fn rcr(self, carry_in: u1) -> (Self, u1) {
+        let [bit0, bit1, bit2, bit3] = self.bits();
+        (Self::from_bits([bit1, bit2, bit3, carry_in]), bit0)
+    }
+
+    /// Converts the Nibble to its reflected binary (gray code) form, where
+    /// consecutive numbers differ by exactly one bit
+    pub fn to_gray(self) -> Self {
+        let bits = self.bits();
+        Self::from_bits(std::array::from_fn(|i| {
+            bits[i] ^ bits.get(i + 1).copied().unwrap_or(b0)
+        }))
+    }
+
+    /// Converts a gray code Nibble back to its binary form
+    pub fn from_gray(gray: Self) -> Self {
+        let gray = gray.bits();
+        let mut bits = gray;
+        for i in (0..3).rev() {
+            bits[i] = gray[i] ^ bits[i + 1];
+        }
+        Self::from_bits(bits)
+    }
+
+    /// Reads the Nibble as a gray code and returns the next value in the
+    /// gray sequence, which differs from it by one bit. The last value
+    /// (`0b1000`, gray code for 15) wraps around to 0.
+    pub fn gray_increment(self) -> Self {
+        Self((Self::from_gray(self).0 + 1) & Self::MAX.0).to_gray()
+    }
+
+    /// Reads the Nibble as a gray code and returns the previous value in the
+    /// gray sequence. 0 wraps around to the last value (`0b1000`).
+    pub fn gray_decrement(self) -> Self {
+        (Self::from_gray(self) - Self(1)).to_gray()
+    }
+
+    /// Returns the top bit, which is one when the Nibble is negative when
+    /// read as a two's complement number
+    fn sign(self) -> u1 {
+        self.bits()[3]
+    }
+
+    /// Returns true if the top bit is set, i.e. the Nibble is negative when
+    /// read as a two's complement number
+    pub fn is_negative(self) -> bool {
+        self.sign().0
+    }
+
+    /// Returns true if the lowest bit is zero
+    pub fn is_even(self) -> bool {
+        !self.bits()[0].0
+    }
+
+    /// Returns true if the lowest bit is one
+    pub fn is_odd(self) -> bool {
+        self.bits()[0].0
+    }
+
+    /// Reads the Nibble as a two's complement number and returns the
+    /// ones' complement encoding of the same value, where negative numbers
+    /// are the bitwise inverse of their magnitude (so -3 is `0b1100`).
+    /// Returns `None` for -8, which has no ones' complement encoding.
+    pub fn ones_complement(self) -> Option<Self> {
+        match (self.sign().0, self == Self(0b1000)) {
+            (false, _) => Some(self),
+            (true, false) => Some(Self::from_bits((Self::MIN - self).bits().map(|bit| !bit))),
+            (true, true) => None,
+        }
+    }
+
+    /// Reads `n` as a ones' complement number and returns the two's
+    /// complement Nibble of the same value. Both zeros (`0b0000` and
+    /// `0b1111`) become 0.
+    pub fn from_ones_complement(n: Self) -> Self {
+        if n.sign().0 {
+            Self::MIN - Self::from_bits(n.bits().map(|bit| !bit))
+        } else {
+            n
+        }
+    }
+
+    /// Reads the Nibble as a two's complement number and returns its sign
+    /// (one for negative) and magnitude, so -3 becomes `(1, 3)` and
+    /// -8 becomes `(1, 8)`
+    pub fn to_sign_magnitude(self) -> (u1, Self) {
+        let sign = self.sign();
+        if sign.0 {
+            (sign, Self::MIN - self)
+        } else {
+            (sign, self)
+        }
+    }
+
+    /// Returns the two's complement Nibble with the given sign (one for
+    /// negative) and magnitude, or `None` if the value is outside `-8..=7`.
+    /// A negative sign with a magnitude of 0 gives 0.
+    pub fn from_sign_magnitude(sign: u1, magnitude: Self) -> Option<Self> {
+        match (sign.0, magnitude.0) {
+            (false, 0..=7) => Some(magnitude),
+            (true, 0..=8) => Some(Self::MIN - magnitude),
+            _ => None,
+        }
+    }
+
+    /// Shifts the Nibble by `amount` (a 2-bit number, least significant bit
+    /// first) in `direction`, filling with zeros.
+    ///
+    /// This is built the way hardware does it: one layer of multiplexers
+    /// shifts by 1 or 0 depending on `amount[0]`, and a second layer shifts
+    /// that result by 2 or 0 depending on `amount[1]`.
+    pub fn barrel_shift(self, amount: [u1; 2], direction: Direction) -> Self {
+        let stage = |bits: [u1; 4], select: u1, distance: usize| {
+            std::array::from_fn(|i| {
+                let shifted = match direction {
+                    Direction::Left => i.checked_sub(distance).map(|from| bits[from]),
+                    Direction::Right => bits.get(i + distance).copied(),
+                };
+                select.select(bits[i], shifted.unwrap_or(b0))
+            })
+        };
+        Self::from_bits(stage(stage(self.bits(), amount[0], 1), amount[1], 2))
+    }
+
+    /// Subtracts `rhs` from the Nibble one bit at a time, returning the
+    /// (wrapped) difference along with the flags a CPU would set.
+    /// See [`Flags`] for what each flag means after a subtraction.
+    pub fn sub_with_flags(self, rhs: Self) -> (Self, Flags) {
+        self.sub_with_borrow(rhs, b0)
+    }
+
+    /// Subtracts `rhs` and `borrow` from the Nibble with a ripple-borrow chain
+    fn sub_with_borrow(self, rhs: Self, mut borrow: u1) -> (Self, Flags) {
+        let (a, b) = (self.bits(), rhs.bits());
+        let mut difference = a;
+        for (bit, &rhs_bit) in difference.iter_mut().zip(&b) {
+            (*bit, borrow) = bit.borrowing_sub(rhs_bit, borrow);
+        }
+        let overflow = (a[3] ^ b[3]) & (a[3] ^ difference[3]);
+        Flags::of(difference, borrow, overflow)
+    }
+
+    /// Performs `op` on the Nibble and `rhs` the way a CPU's arithmetic logic
+    /// unit would, returning the result and the flags it sets.
+    ///
+    /// - `Add` computes `self + rhs + carry_in`, and `Sub` computes
+    ///   `self - rhs - carry_in` with `carry_in` acting as a borrow. Both set
+    ///   every flag as described on [`Flags`].
+    /// - `And`, `Or` and `Xor` are bitwise and never set `carry` or `overflow`.
+    /// - `Shl` and `Shr` shift the Nibble by `rhs` places, filling with
+    ///   zeros. `carry` is the last bit shifted out (zero when `rhs` is 0),
+    ///   and `overflow` is never set.
+    pub fn alu(self, rhs: Self, op: AluOp, carry_in: u1) -> (Self, Flags) {
+        let (a, b) = (self.bits(), rhs.bits());
+        match op {
+            AluOp::Add => {
+                let (sum, carry) = crate::bits::ripple(a, b, carry_in);
+                let overflow = !(a[3] ^ b[3]) & (a[3] ^ sum[3]);
+                Flags::of(sum, carry, overflow)
+            }
+            AluOp::Sub => self.sub_with_borrow(rhs, carry_in),
+            AluOp::And => Flags::of((self & rhs).bits(), b0, b0),
+            AluOp::Or => Flags::of((self | rhs).bits(), b0, b0),
+            AluOp::Xor => Flags::of((self ^ rhs).bits(), b0, b0),
+            AluOp::Shl => {
+                // Bit 4 of the widened result is the last bit shifted out
+                let shifted = u32::from(self.0) << rhs.0;
+                Flags::of(
+                    Self(shifted as u8 & Self::MAX.0).bits(),
+                    u1(shifted & 0b1_0000 > 0),
+                    b0,
+                )
+            }
+            AluOp::Shr => {
+                // Bit 0 of the widened result is the last bit shifted out
+                let shifted = u32::from(self.0) << 1 >> rhs.0;
+                Flags::of(Self((shifted >> 1) as u8).bits(), u1(shifted & 1 > 0), b0)
+            }
+        }
+    }
+}
+
+/// An operation performed by [`Nibble::alu`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AluOp {
+    /// Addition with carry
+    Add,
+
+    /// Subtraction with borrow
+    Sub,
+
+    /// Bitwise and
+    And,
+
+    /// Bitwise or
+    Or,
+
+    /// Bitwise exclusive or
+    Xor,
+
+    /// Logical shift left
+    Shl,
+
+    /// Logical shift right
+    Shr,
+}
+
+impl BitAnd for Nibble {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let (a, b) = (self.bits(), rhs.bits());
+        Self::from_bits(std::array::from_fn(|i| a[i] & b[i]))
+    }
+}
+
+impl BitOr for Nibble {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let (a, b) = (self.bits(), rhs.bits());
+        Self::from_bits(std::array::from_fn(|i| a[i] | b[i]))
+    }
+}
+
+impl BitXor for Nibble {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        let (a, b) = (self.bits(), rhs.bits());
+        Self::from_bits(std::array::from_fn(|i| a[i] ^ b[i]))
+    }
+}
+
+impl Not for Nibble {
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        Self::from_bits(self.bits().map(|bit| !bit))
+    }
+}
+
+/// Wrapping addition, so `15 + 1` is `0`
+impl Add for Nibble {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::from_bits(crate::bits::ripple(self.bits(), rhs.bits(), b0).0)
+    }
+}
+
+/// Wrapping subtraction, so `0 - 1` is `15`
+impl Sub for Nibble {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.sub_with_flags(rhs).0
+    }
+}
+
+/// The array is least significant bit first, like [`Nibble::bits`]
+impl From<[bool; 4]> for Nibble {
+    fn from(value: [bool; 4]) -> Self {
+        Self::from_bits(value.map(u1))
+    }
+}
+
+/// The array is least significant bit first, like [`Nibble::bits`]
+impl From<Nibble> for [bool; 4] {
+    fn from(value: Nibble) -> Self {
+        value.bits().map(u1::get)
+    }
+}
+
+impl ToPrimitive for Nibble {
+    fn to_i64(&self) -> Option<i64> {
+        Some(self.0.into())
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        Some(self.0.into())
+    }
+}
+
+/// Conversions succeed only for values in `0..=15`
+impl FromPrimitive for Nibble {
+    fn from_i64(n: i64) -> Option<Self> {
+        u8::try_from(n).ok().and_then(Self::new)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        u8::try_from(n).ok().and_then(Self::new)
+    }
+}
+
+macro_rules! impl_try_from_int {
+    ($($int:ty),*) => {$(
+        /// Succeeds only if the value fits in a Nibble (0 to 15)
+        impl TryFrom<$int> for Nibble {
+            type Error = OutOfRangeError;
+
+            fn try_from(value: $int) -> Result<Self, Self::Error> {
+                u8::try_from(value)
+                    .ok()
+                    .and_then(Self::new)
+                    .ok_or(OutOfRangeError { value: value.into() })
+            }
+        }
+    )*};
+}
+
+impl_try_from_int!(u8, u16, u32);
+
+/// The error returned when converting an integer larger than 15 to a Nibble
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfRangeError {
+    /// The value that didn't fit
+    pub value: u32,
+}
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is out of range for a Nibble (0..=15)", self.value)
+    }
+}
+
+impl std::error::Error for OutOfRangeError {}
+
+/// The direction of a shift
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Towards the most significant bit
+    Left,
+
+    /// Towards the least significant bit
+    Right,
+}
+
+/// A rearrangement of the four bits of a Nibble
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    /// `mapping[output]` is the input bit position that moves to `output`
+    mapping: [usize; 4],
+}
+
+impl Permutation {
+    /// Returns a permutation where output bit `i` is taken from input bit
+    /// `mapping[i]`, or `None` if `mapping` isn't a permutation of `0..4`
+    pub fn new(mapping: [usize; 4]) -> Option<Self> {
+        let mut seen = [false; 4];
+        for &from in &mapping {
+            if *seen.get(from)? {
+                return None;
+            }
+            seen[from] = true;
+        }
+        Some(Self { mapping })
+    }
+
+    /// Returns the permutation that reverses the bit order, so `0b1100`
+    /// becomes `0b0011`
+    pub fn reverse() -> Self {
+        Self {
+            mapping: [3, 2, 1, 0],
+        }
+    }
+
+    /// Returns the permutation that swaps bits 0 and 1, and bits 2 and 3,
+    /// so `0b1001` becomes `0b0110`
+    pub fn swap_adjacent_pairs() -> Self {
+        Self {
+            mapping: [1, 0, 3, 2],
+        }
+    }
+
+    /// Returns the permutation that rotates the bits `n` places towards the
+    /// most significant bit (modulo 4), so `rotate(1)` turns `0b1100` into
+    /// `0b1001`
+    pub fn rotate(n: u32) -> Self {
+        Self {
+            mapping: std::array::from_fn(|i| (i + 4 - n as usize % 4) % 4),
+        }
+    }
+
+    /// Returns the mapping from output bit positions to input bit positions
+    pub fn mapping(self) -> [usize; 4] {
+        self.mapping
+    }
+
+    /// Rearranges the bits of `n` according to the permutation
+    pub fn apply(self, n: Nibble) -> Nibble {
+        let bits = n.bits();
+        Nibble::from_bits(self.mapping.map(|from| bits[from]))
+    }
+}
+
+/// A 4-input look-up table, like the ones FPGAs are built from.
+/// Entry `i` is the output when the inputs, read as a Nibble, equal `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lut4(pub [u1; 16]);
+
+impl Lut4 {
+    /// Returns the table's output for `inputs`
+    pub fn eval(&self, inputs: Nibble) -> u1 {
+        self.0[usize::from(inputs.get())]
+    }
+}
+
+/// A 4-bit counter, like a chain of flip-flops each toggling the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RippleCounter {
+    value: Nibble,
+}
+
+impl RippleCounter {
+    /// Returns a counter starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current count
+    pub fn value(self) -> Nibble {
+        self.value
+    }
+
+    /// Adds one to the count, returning true if it overflowed from 15 back
+    /// to 0
+    pub fn tick(&mut self) -> bool {
+        let mut bits = self.value.bits();
+        let overflow = crate::bits::increment(&mut bits);
+        self.value = Nibble::from_bits(bits);
+        overflow
+    }
+
+    /// Sets the count back to zero
+    pub fn reset(&mut self) {
+        self.value = Nibble::MIN;
+    }
+}
+
+/// A 4-bit counter whose output is Gray coded, so exactly one bit changes
+/// on every tick, including the wrap from the last code back to zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GrayCounter {
+    binary: RippleCounter,
+}
+
+impl GrayCounter {
+    /// Returns a counter starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current count, Gray coded
+    pub fn value(self) -> Nibble {
+        self.binary.value().to_gray()
+    }
+
+    /// Advances to the next Gray code, returning true if the count wrapped
+    /// back to zero
+    pub fn tick(&mut self) -> bool {
+        self.binary.tick()
+    }
+
+    /// Sets the count back to zero
+    pub fn reset(&mut self) {
+        self.binary.reset();
+    }
+}
+
+/// Whether a parity bit makes the number of ones even or odd
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parity {
+    /// The code word has an even number of ones
+    Even,
+
+    /// The code word has an odd number of ones
+    Odd,
+}
+
+impl Parity {
+    /// Returns the parity bit to send alongside `n`
+    fn bit_for(self, n: Nibble) -> u1 {
+        match self {
+            Self::Even => n.parity(),
+            Self::Odd => !n.parity(),
+        }
+    }
+}
+
+/// The error returned by [`Nibble::check_parity`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParityError {
+    /// The code word has bits set above bit 4
+    TooWide(u8),
+
+    /// The parity bit doesn't match the Nibble, so a bit was corrupted
+    Mismatch,
+}
+
+impl fmt::Display for ParityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooWide(code) => write!(f, "code word {code:#b} is wider than 5 bits"),
+            Self::Mismatch => write!(f, "parity check failed"),
+        }
+    }
+}
+
+impl std::error::Error for ParityError {}
+
+/// A 4-bit substitution box, as used in block ciphers.
+/// Entry `i` is the output for an input Nibble of value `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SBox(pub [Nibble; 16]);
+
+impl SBox {
+    /// Returns the output for `input`
+    pub fn substitute(self, input: Nibble) -> Nibble {
+        self.0[usize::from(input.get())]
+    }
+
+    /// Returns the S-box that undoes this one, or an error naming the first
+    /// output that two inputs map to, since then it can't be undone
+    pub fn inverse(self) -> Result<Self, SBoxError> {
+        let mut inverse = [None; 16];
+        for (input, output) in (0..).zip(self.0) {
+            let entry = &mut inverse[usize::from(output.get())];
+            if entry.is_some() {
+                return Err(SBoxError { output });
+            }
+            *entry = Some(Nibble(input));
+        }
+        Ok(Self(inverse.map(|entry| {
+            entry.expect("16 distinct outputs cover every Nibble")
+        })))
+    }
+}
+
+/// The error returned by [`SBox::inverse`] when the S-box isn't a bijection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SBoxError {
+    /// An output that more than one input maps to
+    pub output: Nibble,
+}
+
+impl fmt::Display for SBoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "S-box output {} is produced by more than one input",
+            self.output.get()
+        )
+    }
+}
+
+impl std::error::Error for SBoxError {}
+
+/// Status flags describing the result of an operation on [`Nibble`]s
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Flags {
+    /// One if every bit of the result is zero
+    pub zero: u1,
+
+    /// One if the operation carried out of the top bit. For subtraction this
+    /// is the borrow, so it is one exactly when `lhs < rhs` as unsigned numbers.
+    pub carry: u1,
+
+    /// A copy of the top bit of the result, which is one when the result is
+    /// negative in two's complement
+    pub negative: u1,
+
+    /// One if the result doesn't fit in a signed (two's complement) Nibble.
+    /// For subtraction this happens when the operands have different signs
+    /// and the sign of the result differs from the sign of `lhs`.
+    pub overflow: u1,
+}
+
+impl Flags {
+    /// Returns `result` as a Nibble along with its flags, deriving `zero`
+    /// and `negative` from `result`
+    fn of(result: [u1; 4], carry: u1, overflow: u1) -> (Nibble, Self) {
+        let result = Nibble::from_bits(result);
+        let flags = Self {
+            zero: u1(result == Nibble::MIN),
+            carry,
+            negative: result.sign(),
+            overflow,
+        };
+        (result, flags)
+    }
+}
+
+impl Ord for Nibble {
+    fn cmp(&self, other: &Self) -> Ordering {
+        crate::bits::bits_cmp(&self.bits(), &other.bits())
+    }
+}
+
+impl PartialOrd for Nibble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Returns the bits of `value` that are set in `mask`, clearing the rest
+pub fn apply_mask(value: Nibble, mask: Nibble) -> Nibble {
+    value & mask
+}
+
+/// Overwrites the bits of `value` that are set in `mask` with the matching
+/// bits of `bits`, leaving the others untouched (a read-modify-write)
+pub fn masked_set(value: Nibble, mask: Nibble, bits: Nibble) -> Nibble {
+    (value & !mask) | (bits & mask)
+}
+
+/// Adds two binary-coded decimal digits and a carry, returning the decimal
+/// digit of the sum and the decimal carry, so `7 + 5` is `2` carry `1`.
+///
+/// This is the classic BCD adder: the digits are added in binary, and 6 is
+/// added to the result whenever it exceeds 9 to skip the six unused codes.
+/// Inputs above 9 aren't valid digits and give meaningless results.
+pub fn bcd_add(a: Nibble, b: Nibble, carry_in: u1) -> (Nibble, u1) {
+    use crate::bits::ripple;
+
+    let (sum, binary_carry) = ripple(a.bits(), b.bits(), carry_in);
+    let decimal_carry = binary_carry | (sum[3] & (sum[2] | sum[1]));
+    let correction = [b0, decimal_carry, decimal_carry, b0];
+    let (digit, _) = ripple(sum, correction, b0);
+
+    (Nibble::from_bits(digit), decimal_carry)
+}
+
+/// Interleaves the bits of two Nibbles into a byte (a Morton / Z-order code):
+/// bit `i` of `a` becomes bit `2i` of the result, and bit `i` of `b` becomes
+/// bit `2i + 1`
+pub fn interleave(a: Nibble, b: Nibble) -> u8 {
+    let (a, b) = (a.bits(), b.bits());
+    crate::u8_from_bits(std::array::from_fn(|i| {
+        if i % 2 == 0 {
+            a[i / 2]
+        } else {
+            b[i / 2]
+        }
+    }))
+}
+
+/// Splits a Morton code back into the two Nibbles passed to [`interleave`]
+pub fn deinterleave(code: u8) -> (Nibble, Nibble) {
+    let bits = crate::bits_of_u8(code);
+    (
+        Nibble::from_bits(std::array::from_fn(|i| bits[2 * i])),
+        Nibble::from_bits(std::array::from_fn(|i| bits[2 * i + 1])),
+    )
+}
+
+/// Gray-encodes every Nibble in `nibbles` with [`Nibble::to_gray`]
+pub fn gray_encode_slice(nibbles: &[Nibble]) -> Vec<Nibble> {
+    nibbles.iter().map(|n| n.to_gray()).collect()
+}
+
+/// Decodes every Gray-coded Nibble in `codes` with [`Nibble::from_gray`]
+pub fn gray_decode_slice(codes: &[Nibble]) -> Vec<Nibble> {
+    codes.iter().map(|&code| Nibble::from_gray(code)).collect()
+}
+
+/// Compares two multi-nibble numbers stored most significant nibble first,
+/// so `[1, 0, 0]` is 0x100. The shorter slice is padded with leading zeros,
+/// so `[0, 5]` and `[5]` are equal.
+pub fn cmp_nibbles(a: &[Nibble], b: &[Nibble]) -> Ordering {
+    let len = a.len().max(b.len());
+    // The `i`th nibble from the most significant end, after padding
+    let nibble = |nibbles: &[Nibble], i: usize| {
+        (i + nibbles.len())
+            .checked_sub(len)
+            .map_or(Nibble::MIN, |j| nibbles[j])
+    };
+    (0..len)
+        .map(|i| nibble(a, i).cmp(&nibble(b, i)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Returns the largest Nibble in `nibbles`, or `None` if it is empty.
+/// Nibbles are compared as unsigned numbers, so `0b1000` (8) is larger
+/// than `0b0111` (7) even though it is negative in two's complement.
+pub fn max_nibble(nibbles: &[Nibble]) -> Option<Nibble> {
+    nibbles.iter().copied().max()
+}
+
+/// Returns the smallest Nibble in `nibbles`, or `None` if it is empty.
+/// Nibbles are compared as unsigned numbers, like in [`max_nibble`].
+pub fn min_nibble(nibbles: &[Nibble]) -> Option<Nibble> {
+    nibbles.iter().copied().min()
+}
+
+/// Converts `n` to a 15-bit thermometer code, where a value of `k` sets
+/// the low `k` bits, so `3` becomes `0b111`
+pub fn to_thermometer(n: Nibble) -> u16 {
+    (1 << n.get()) - 1
+}
+
+/// Converts a thermometer code back into the Nibble it encodes, or returns
+/// `None` if `code` isn't a run of ones starting at bit 0 that fits in
+/// 15 bits
+pub fn from_thermometer(code: u16) -> Option<Nibble> {
+    if code & code.wrapping_add(1) != 0 {
+        return None;
+    }
+    Nibble::new(code.count_ones() as u8)
+}
+
+/// Parses a string of hexadecimal digits into Nibbles, one per digit.
+///
+/// Both upper and lower case digits are accepted, so `"1aF"` becomes the
+/// Nibbles `1`, `10` and `15` in that order.
+pub fn parse_hex(s: &str) -> Result<Vec<Nibble>, ParseHexError> {
+    s.chars()
+        .enumerate()
+        .map(|(index, c)| Nibble::from_digit(c).ok_or(ParseHexError { index, found: c }))
+        .collect()
+}
+
+/// The error returned by [`parse_hex`] when it finds a non-hexadecimal character
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseHexError {
+    /// Index (in chars) of the first invalid character
+    pub index: usize,
+
+    /// The invalid character
+    pub found: char,
+}
+
+impl fmt::Display for ParseHexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid hex digit {:?} at index {}",
+            self.found, self.index
+        )
+    }
+}
+
+impl std::error::Error for ParseHexError {}
+
+// Returns the Nibble holding `v`, for building test values
+#[cfg(test)]
+fn n(v: u8) -> Nibble {
+    Nibble::new(v).unwrap()
+}
+
+#[test]
+fn parse_hex_works() {
+    let nibbles: Vec<u8> = parse_hex("1aF")
+        .unwrap()
+        .into_iter()
+        .map(Nibble::get)
+        .collect();
+    assert_eq!(nibbles, [1, 10, 15]);
+    assert_eq!(parse_hex(""), Ok(vec![]));
+}
+
+#[test]
+fn parse_hex_rejects_invalid_digits() {
+    assert_eq!(
+        parse_hex("12g4"),
+        Err(ParseHexError {
+            index: 2,
+            found: 'g'
+        })
+    );
+    assert_eq!(
+        parse_hex("-1").unwrap_err().to_string(),
+        "invalid hex digit '-' at index 0"
+    );
+}
+
+#[test]
+fn nibble_ord_works() {
+    let [two, three, nine] = [2, 3, 9].map(|n| Nibble::new(n).unwrap());
+    assert!(two < three && three < nine);
+    assert_eq!(Nibble::MAX.max(nine), Nibble::MAX);
+}
+
+#[test]
+fn sub_with_flags_works() {
+    use crate::u1::b1;
+
+    // 6 - 4 = 2, no flags
+    assert_eq!(n(6).sub_with_flags(n(4)), (n(2), Flags::default()));
+
+    // 3 - 3 = 0
+    let (result, flags) = n(3).sub_with_flags(n(3));
+    assert_eq!((result, flags.zero, flags.carry), (n(0), b1, b0));
+
+    // 2 - 5 borrows, -3 is 0b1101
+    let (result, flags) = n(2).sub_with_flags(n(5));
+    assert_eq!(result, n(0b1101));
+    assert_eq!((flags.carry, flags.negative, flags.overflow), (b1, b1, b0));
+}
+
+#[test]
+fn sub_with_flags_sets_overflow() {
+    use crate::u1::b1;
+
+    // Signed: -8 - 1 = -9, which doesn't fit and wraps to +7
+    let (result, flags) = n(0b1000).sub_with_flags(n(1));
+    assert_eq!(result, n(7));
+    assert_eq!(
+        flags,
+        Flags {
+            zero: b0,
+            carry: b0,
+            negative: b0,
+            overflow: b1
+        }
+    );
+
+    // Signed: 7 - (-1) = 8, which doesn't fit and wraps to -8
+    let (result, flags) = n(7).sub_with_flags(n(0b1111));
+    assert_eq!(result, n(0b1000));
+    assert_eq!((flags.carry, flags.negative, flags.overflow), (b1, b1, b1));
+    assert_eq!(n(0) - n(1), Nibble::MAX);
+}
+
+#[test]
+fn swap_halves_works() {
+    assert_eq!(n(0b1101).swap_halves(), n(0b0111));
+    assert_eq!(n(0b0011).swap_halves(), n(0b1100));
+    assert_eq!(n(0b1001).swap_halves().swap_halves(), n(0b1001));
+}
+
+#[test]
+fn from_primitive_works() {
+    assert_eq!(Nibble::from_u8(5), Nibble::new(5));
+    assert_eq!(Nibble::from_i32(15), Some(Nibble::MAX));
+    assert_eq!(Nibble::from_u8(16), None);
+    assert_eq!(Nibble::from_i64(-1), None);
+    assert_eq!(Nibble::MAX.to_u8(), Some(15));
+    assert_eq!(Nibble::MAX.to_i8(), Some(15));
+}
+
+#[test]
+fn rotate_through_carry_works() {
+    use crate::u1::b1;
+
+    assert_eq!(n(0b1001).rcl(b0), (n(0b0010), b1));
+    assert_eq!(n(0b0010).rcl(b1), (n(0b0101), b0));
+    assert_eq!(n(0b1001).rcr(b0), (n(0b0100), b1));
+    assert_eq!(n(0b0100).rcr(b1), (n(0b1010), b0));
+
+    // Five rotations through the carry restore the original value
+    let (mut value, mut carry) = (n(0b1011), b0);
+    for _ in 0..5 {
+        (value, carry) = value.rcl(carry);
+    }
+    assert_eq!((value, carry), (n(0b1011), b0));
+}
+
+#[test]
+fn signed_encodings_work() {
+    use crate::u1::b1;
+
+    // -3 in each representation
+    let twos = n(0b1101);
+    assert_eq!(twos.ones_complement(), Some(n(0b1100)));
+    assert_eq!(twos.to_sign_magnitude(), (b1, n(3)));
+    assert_eq!(Nibble::from_ones_complement(n(0b1100)), twos);
+    assert_eq!(Nibble::from_sign_magnitude(b1, n(3)), Some(twos));
+
+    // Positive numbers are the same in every representation
+    assert_eq!(n(5).ones_complement(), Some(n(5)));
+    assert_eq!(n(5).to_sign_magnitude(), (b0, n(5)));
+
+    // Edge cases
+    assert_eq!(n(0b1000).ones_complement(), None);
+    assert_eq!(n(0b1000).to_sign_magnitude(), (b1, n(8)));
+    assert_eq!(Nibble::from_ones_complement(n(0b1111)), n(0));
+    assert_eq!(Nibble::from_sign_magnitude(b1, n(0)), Some(n(0)));
+    assert_eq!(Nibble::from_sign_magnitude(b0, n(8)), None);
+    assert_eq!(Nibble::from_sign_magnitude(b1, n(9)), None);
+}
+
+#[test]
+fn gray_sequence_works() {
+    let mut gray = Nibble::MIN;
+    for i in 0..16 {
+        assert_eq!(gray, Nibble::new(i).unwrap().to_gray());
+        assert_eq!(Nibble::from_gray(gray).get(), i);
+
+        let next = gray.gray_increment();
+        assert_eq!((gray.get() ^ next.get()).count_ones(), 1);
+        assert_eq!(next.gray_decrement(), gray);
+        gray = next;
+    }
+    assert_eq!(gray, Nibble::MIN);
+    assert_eq!(Nibble::MIN.gray_decrement(), Nibble::new(0b1000).unwrap());
+}
+
+#[test]
+fn highest_set_bit_works() {
+    assert_eq!(n(0b0110).highest_set_bit(), Some(2));
+    assert_eq!(n(0b1000).highest_set_bit(), Some(3));
+    assert_eq!(n(0b0001).highest_set_bit(), Some(0));
+    assert_eq!(n(0).highest_set_bit(), None);
+}
+
+#[test]
+fn barrel_shift_matches_plain_shift() {
+    for n in 0..16 {
+        for amount in 0..4 {
+            let nibble = Nibble::new(n).unwrap();
+            let bits = [u1(amount & 1 > 0), u1(amount & 2 > 0)];
+            assert_eq!(
+                nibble.barrel_shift(bits, Direction::Left).get(),
+                (n << amount) & 0b1111
+            );
+            assert_eq!(
+                nibble.barrel_shift(bits, Direction::Right).get(),
+                n >> amount
+            );
+        }
+    }
+}
+
+#[test]
+fn splat_works() {
+    use crate::u1::b1;
+
+    assert_eq!(Nibble::splat(b1), Nibble::new(0b1111).unwrap());
+    assert_eq!(Nibble::splat(b0), Nibble::MIN);
+}
+
+#[test]
+fn interleave_round_trip_works() {
+    assert_eq!(interleave(n(0b1111), n(0)), 0b0101_0101);
+    assert_eq!(interleave(n(0), n(0b1111)), 0b1010_1010);
+    assert_eq!(interleave(n(0b0011), n(0b0101)), 0b0010_0111);
+
+    for a in 0..16 {
+        for b in 0..16 {
+            assert_eq!(deinterleave(interleave(n(a), n(b))), (n(a), n(b)));
+        }
+    }
+}
+
+#[test]
+fn leading_and_trailing_ones_work() {
+    assert_eq!(
+        (n(0b1110).leading_ones(), n(0b1110).trailing_ones()),
+        (3, 0)
+    );
+    assert_eq!(
+        (n(0b0111).leading_ones(), n(0b0111).trailing_ones()),
+        (0, 3)
+    );
+    assert_eq!(
+        (Nibble::MAX.leading_ones(), Nibble::MAX.trailing_ones()),
+        (4, 4)
+    );
+}
+
+#[test]
+fn logic_operators_work() {
+    assert_eq!(n(0b1100) & n(0b1010), n(0b1000));
+    assert_eq!(n(0b1100) | n(0b1010), n(0b1110));
+    assert_eq!(n(0b1100) ^ n(0b1010), n(0b0110));
+    assert_eq!(!n(0b1100), n(0b0011));
+}
+
+#[test]
+fn masks_work() {
+    assert_eq!(apply_mask(n(0b1011), n(0b0110)), n(0b0010));
+    assert_eq!(masked_set(n(0b1011), n(0b0110), n(0b0100)), n(0b1101));
+    assert_eq!(masked_set(n(0b1011), n(0), n(0b0100)), n(0b1011));
+    assert_eq!(masked_set(n(0b1011), Nibble::MAX, n(0b0100)), n(0b0100));
+}
+
+#[test]
+fn bcd_add_works() {
+    use crate::u1::b1;
+
+    assert_eq!(bcd_add(n(7), n(5), b0), (n(2), b1));
+    assert_eq!(bcd_add(n(3), n(4), b0), (n(7), b0));
+    assert_eq!(bcd_add(n(9), n(9), b1), (n(9), b1));
+    assert_eq!(bcd_add(n(5), n(4), b1), (n(0), b1));
+
+    for a in 0..10 {
+        for b in 0..10 {
+            let (digit, carry) = bcd_add(n(a), n(b), b0);
+            assert_eq!(u8::from(carry) * 10 + digit.get(), a + b);
+        }
+    }
+}
+
+#[test]
+fn hex_digits_work() {
+    assert_eq!(Nibble::from_digit('7'), Some(n(7)));
+    assert_eq!(Nibble::from_digit('b'), Some(n(11)));
+    assert_eq!(Nibble::from_digit('B'), Some(n(11)));
+    assert_eq!(Nibble::from_digit('g'), None);
+    assert_eq!(n(7).to_digit(true), '7');
+    assert_eq!(n(12).to_digit(false), 'c');
+    assert_eq!(n(12).to_digit(true), 'C');
+}
+
+#[test]
+fn alu_works() {
+    use crate::u1::b1;
+
+    let flags = |[zero, carry, negative, overflow]: [u1; 4]| Flags {
+        zero,
+        carry,
+        negative,
+        overflow,
+    };
+
+    // 7 + 1 = 8 overflows into the sign bit
+    assert_eq!(
+        n(7).alu(n(1), AluOp::Add, b0),
+        (n(8), flags([b0, b0, b1, b1]))
+    );
+    // 15 + 0 + carry wraps to 0
+    assert_eq!(
+        n(15).alu(n(0), AluOp::Add, b1),
+        (n(0), flags([b1, b1, b0, b0]))
+    );
+    // 5 - 2 - borrow = 2
+    assert_eq!(n(5).alu(n(2), AluOp::Sub, b1), (n(2), flags([b0; 4])));
+    // 1 - 2 borrows
+    assert_eq!(
+        n(1).alu(n(2), AluOp::Sub, b0),
+        (n(15), flags([b0, b1, b1, b0]))
+    );
+
+    assert_eq!(
+        n(0b1100).alu(n(0b0011), AluOp::And, b1),
+        (n(0), flags([b1, b0, b0, b0]))
+    );
+    assert_eq!(
+        n(0b1100).alu(n(0b0011), AluOp::Or, b1),
+        (n(15), flags([b0, b0, b1, b0]))
+    );
+    assert_eq!(
+        n(0b1100).alu(n(0b0101), AluOp::Xor, b0),
+        (n(0b1001), flags([b0, b0, b1, b0]))
+    );
+
+    assert_eq!(
+        n(0b1010).alu(n(1), AluOp::Shl, b0),
+        (n(0b0100), flags([b0, b1, b0, b0]))
+    );
+    assert_eq!(
+        n(0b1010).alu(n(2), AluOp::Shl, b0),
+        (n(0b1000), flags([b0, b0, b1, b0]))
+    );
+    assert_eq!(
+        n(0b0101).alu(n(1), AluOp::Shr, b0),
+        (n(0b0010), flags([b0, b1, b0, b0]))
+    );
+    assert_eq!(
+        n(0b0101).alu(n(0), AluOp::Shr, b1),
+        (n(0b0101), flags([b0; 4]))
+    );
+    assert_eq!(
+        n(0b1111).alu(n(9), AluOp::Shr, b0),
+        (n(0), flags([b1, b0, b0, b0]))
+    );
+}
+
+#[test]
+fn add_wraps() {
+    assert_eq!(n(6) + n(7), n(13));
+    assert_eq!(n(15) + n(1), n(0));
+    assert_eq!(n(9) + n(9), n(2));
+}
+
+#[test]
+fn to_binary_string_works() {
+    let n = Nibble::new(0b0011).unwrap();
+    assert_eq!(n.to_binary_string(true), "0011");
+    assert_eq!(n.to_binary_string(false), "1100");
+}
+
+#[test]
+fn permutation_works() {
+    let identity = Permutation::new([0, 1, 2, 3]).unwrap();
+    let swap = Permutation::new([1, 0, 2, 3]).unwrap();
+    for i in 0..16 {
+        assert_eq!(identity.apply(n(i)), n(i));
+    }
+    assert_eq!(swap.apply(n(0b0001)), n(0b0010));
+    assert_eq!(swap.apply(n(0b1110)), n(0b1101));
+
+    assert_eq!(Permutation::new([0, 1, 1, 3]), None);
+    assert_eq!(Permutation::new([0, 1, 2, 4]), None);
+}
+
+#[test]
+fn indexed_bits_works() {
+    use crate::u1::b1;
+
+    let pairs: Vec<_> = Nibble::new(0b0110).unwrap().indexed_bits().collect();
+    assert_eq!(pairs, [(0, b0), (1, b1), (2, b1), (3, b0)]);
+}
+
+#[test]
+fn checked_and_wrapping_shl_work() {
+    assert_eq!(n(0b1011).checked_shl(0), Some(n(0b1011)));
+    assert_eq!(n(0b1011).checked_shl(3), Some(n(0b1000)));
+    assert_eq!(n(0b1011).checked_shl(5), None);
+
+    assert_eq!(n(0b1011).wrapping_shl(0), n(0b1011));
+    assert_eq!(n(0b1011).wrapping_shl(3), n(0b1000));
+    assert_eq!(n(0b1011).wrapping_shl(5), n(0b0110));
+}
+
+#[test]
+fn parity_works() {
+    use crate::u1::b1;
+
+    assert_eq!(n(0b0000).parity(), b0);
+    assert_eq!(n(0b0110).parity(), b0);
+    assert_eq!(n(0b1111).parity(), b0);
+    assert_eq!(n(0b0100).parity(), b1);
+    assert_eq!(n(0b1101).parity(), b1);
+}
+
+#[test]
+fn bool_array_round_trip_works() {
+    assert_eq!(
+        Nibble::from([true, false, false, true]),
+        Nibble::new(0b1001).unwrap()
+    );
+    for i in 0..16 {
+        let n = Nibble::new(i).unwrap();
+        assert_eq!(Nibble::from(<[bool; 4]>::from(n)), n);
+    }
+}
+
+#[test]
+fn lut4_works() {
+    let and4 = Lut4(std::array::from_fn(|i| u1(i == 0b1111)));
+    for i in 0..16 {
+        let inputs = Nibble::new(i).unwrap();
+        let expected = inputs.bits().into_iter().fold(u1(true), |a, b| a & b);
+        assert_eq!(and4.eval(inputs), expected);
+    }
+}
+
+#[test]
+fn thermometer_code_works() {
+    assert_eq!(to_thermometer(n(0)), 0);
+    assert_eq!(to_thermometer(n(7)), 0b0111_1111);
+    assert_eq!(to_thermometer(n(15)), 0x7fff);
+    for i in 0..16 {
+        assert_eq!(from_thermometer(to_thermometer(n(i))), Some(n(i)));
+    }
+    assert_eq!(from_thermometer(0b1011), None);
+    assert_eq!(from_thermometer(0b0110), None);
+    assert_eq!(from_thermometer(0xffff), None);
+}
+
+#[test]
+fn try_from_int_works() {
+    assert_eq!(Nibble::try_from(15_u8), Ok(n(15)));
+    assert_eq!(Nibble::try_from(16_u8), Err(OutOfRangeError { value: 16 }));
+    assert_eq!(Nibble::try_from(7_u16), Ok(n(7)));
+    assert_eq!(
+        Nibble::try_from(0x100_u16),
+        Err(OutOfRangeError { value: 0x100 })
+    );
+    assert_eq!(Nibble::try_from(0_u32), Ok(n(0)));
+    assert_eq!(
+        Nibble::try_from(u32::MAX),
+        Err(OutOfRangeError { value: u32::MAX })
+    );
+}
+
+#[test]
+fn permutation_presets_work() {
+    assert_eq!(Permutation::reverse().apply(n(0b1100)), n(0b0011));
+    assert_eq!(
+        Permutation::swap_adjacent_pairs().apply(n(0b1100)),
+        n(0b1100)
+    );
+    assert_eq!(
+        Permutation::swap_adjacent_pairs().apply(n(0b1001)),
+        n(0b0110)
+    );
+    assert_eq!(Permutation::rotate(0).apply(n(0b1100)), n(0b1100));
+    assert_eq!(Permutation::rotate(1).apply(n(0b1100)), n(0b1001));
+    assert_eq!(Permutation::rotate(3).apply(n(0b1100)), n(0b0110));
+    assert_eq!(Permutation::rotate(5), Permutation::rotate(1));
+    for preset in [
+        Permutation::reverse(),
+        Permutation::swap_adjacent_pairs(),
+        Permutation::rotate(2),
+    ] {
+        assert_eq!(Permutation::new(preset.mapping()), Some(preset));
+    }
+}
+
+#[test]
+fn char_radix_works() {
+    assert_eq!(n(1).to_char_radix(2), Some('1'));
+    assert_eq!(n(2).to_char_radix(2), None);
+    assert_eq!(Nibble::from_char_radix('1', 2), Some(n(1)));
+    assert_eq!(Nibble::from_char_radix('2', 2), None);
+
+    assert_eq!(n(9).to_char_radix(10), Some('9'));
+    assert_eq!(n(10).to_char_radix(10), None);
+    assert_eq!(Nibble::from_char_radix('7', 10), Some(n(7)));
+    assert_eq!(Nibble::from_char_radix('a', 10), None);
+
+    assert_eq!(n(15).to_char_radix(16), Some('f'));
+    assert_eq!(Nibble::from_char_radix('F', 16), Some(n(15)));
+
+    assert_eq!(n(0).to_char_radix(1), None);
+    assert_eq!(n(0).to_char_radix(17), None);
+    assert_eq!(Nibble::from_char_radix('0', 36), None);
+}
+
+#[test]
+fn cmp_nibbles_works() {
+    let num = |digits: [u8; 3]| digits.map(|d| Nibble::new(d).unwrap());
+
+    assert_eq!(
+        cmp_nibbles(&num([1, 0, 15]), &num([0, 15, 15])),
+        Ordering::Greater
+    );
+    assert_eq!(
+        cmp_nibbles(&num([3, 4, 5]), &num([3, 5, 0])),
+        Ordering::Less
+    );
+    assert_eq!(
+        cmp_nibbles(&num([7, 7, 7]), &num([7, 7, 7])),
+        Ordering::Equal
+    );
+    assert_eq!(
+        cmp_nibbles(&num([0, 0, 5]), &num([0, 5, 0])[1..]),
+        Ordering::Less
+    );
+    assert_eq!(
+        cmp_nibbles(&num([0, 0, 5]), &num([0, 0, 5])[2..]),
+        Ordering::Equal
+    );
+    assert_eq!(cmp_nibbles(&[], &[]), Ordering::Equal);
+}
+
+#[test]
+fn parity_predicates_and_is_negative_work() {
+    assert!(n(0).is_even() && !n(0).is_odd());
+    assert!(n(6).is_even() && !n(6).is_odd());
+    assert!(n(7).is_odd() && !n(7).is_even());
+    assert!(n(15).is_odd());
+
+    assert!(!n(0).is_negative());
+    assert!(!n(7).is_negative());
+    assert!(n(8).is_negative());
+    assert!(n(15).is_negative());
+}
+
+#[test]
+fn saturating_add_and_sub_work() {
+    assert_eq!(n(7).saturating_add(n(8)), n(15));
+    assert_eq!(n(8).saturating_add(n(8)), n(15));
+    assert_eq!(n(15).saturating_add(n(15)), n(15));
+    assert_eq!(n(3).saturating_add(n(4)), n(7));
+
+    assert_eq!(n(8).saturating_sub(n(8)), n(0));
+    assert_eq!(n(7).saturating_sub(n(8)), n(0));
+    assert_eq!(n(0).saturating_sub(n(15)), n(0));
+    assert_eq!(n(9).saturating_sub(n(4)), n(5));
+}
+
+#[test]
+fn max_and_min_nibble_work() {
+    let nibbles = [7, 8, 0, 15, 3].map(n);
+
+    assert_eq!(max_nibble(&nibbles), Nibble::new(15));
+    assert_eq!(min_nibble(&nibbles), Nibble::new(0));
+    assert_eq!(max_nibble(&nibbles[..2]), Nibble::new(8));
+    assert_eq!(max_nibble(&[]), None);
+    assert_eq!(min_nibble(&[]), None);
+}
+
+#[test]
+fn mac_works() {
+    assert_eq!(n(1).mac(n(2), n(3)), (7, false));
+    assert_eq!(n(0).mac(n(0), n(15)), (0, false));
+    assert_eq!(n(15).mac(n(0), n(0)), (15, false));
+    assert_eq!(n(1).mac(n(5), n(3)), (16, true));
+    assert_eq!(n(15).mac(n(15), n(15)), (240, true));
+}
+
+#[test]
+fn gray_slice_round_trip_works() {
+    let nibbles = [0, 1, 2, 7, 8, 15].map(n);
+    let codes = gray_encode_slice(&nibbles);
+
+    assert_eq!(codes[2], Nibble::new(0b0011).unwrap());
+    assert_eq!(codes[5], Nibble::new(0b1000).unwrap());
+    assert_eq!(gray_decode_slice(&codes), nibbles);
+    assert_eq!(gray_encode_slice(&[]), []);
+}
+
+#[test]
+fn ripple_counter_works() {
+    let mut counter = RippleCounter::new();
+    for i in 1..16 {
+        assert!(!counter.tick());
+        assert_eq!(counter.value().get(), i);
+    }
+    assert!(counter.tick());
+    assert_eq!(counter.value(), Nibble::MIN);
+
+    counter.tick();
+    counter.tick();
+    counter.reset();
+    assert_eq!(counter, RippleCounter::new());
+}
+
+#[test]
+fn gray_counter_changes_one_bit_per_tick() {
+    let mut counter = GrayCounter::new();
+    let mut seen = std::collections::HashSet::new();
+    for tick in 1..=16 {
+        let before = counter.value();
+        let overflow = counter.tick();
+        assert_eq!((before ^ counter.value()).get().count_ones(), 1);
+        assert_eq!(overflow, tick == 16);
+        seen.insert(counter.value());
+    }
+    assert_eq!(seen.len(), 16);
+    assert_eq!(counter.value(), Nibble::MIN);
+}
+
+#[test]
+fn parity_code_words_work() {
+    assert_eq!(n(0b0111).with_parity(Parity::Even), 0b1_0111);
+    assert_eq!(n(0b0111).with_parity(Parity::Odd), 0b0_0111);
+    assert_eq!(n(0b0110).with_parity(Parity::Even), 0b0_0110);
+
+    for i in 0..16 {
+        for mode in [Parity::Even, Parity::Odd] {
+            let code = n(i).with_parity(mode);
+            assert_eq!(Nibble::check_parity(code, mode), Ok(n(i)));
+            for flipped in 0..5 {
+                assert_eq!(
+                    Nibble::check_parity(code ^ 1 << flipped, mode),
+                    Err(ParityError::Mismatch)
+                );
+            }
+        }
+    }
+    assert_eq!(
+        Nibble::check_parity(0b10_0000, Parity::Even),
+        Err(ParityError::TooWide(0b10_0000))
+    );
+}
+
+#[test]
+fn arithmetic_shr_works() {
+    // Reads the Nibble as a two's complement number
+    let signed = |n: Nibble| (n.get() << 4) as i8 >> 4;
+
+    for i in 0..16 {
+        for shift in 0..4 {
+            assert_eq!(signed(n(i).arithmetic_shr(shift)), signed(n(i)) >> shift);
+        }
+    }
+    assert_eq!(n(0b1010).arithmetic_shr(1), n(0b1101));
+    assert_eq!(n(0b1010).arithmetic_shr(9), n(0b1111));
+    assert_eq!(n(0b0110).arithmetic_shr(1), n(0b0011));
+    assert_eq!(n(0b0110).arithmetic_shr(4), n(0));
+}
+
+#[test]
+fn sbox_works() {
+    // The PRESENT cipher's S-box
+    let sbox = SBox(
+        [
+            0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2,
+        ]
+        .map(n),
+    );
+    assert_eq!(sbox.substitute(Nibble::MIN), Nibble::new(0xC).unwrap());
+    assert_eq!(sbox.substitute(Nibble::MAX), Nibble::new(0x2).unwrap());
+
+    let inverse = sbox.inverse().unwrap();
+    for i in 0..16 {
+        let n = Nibble::new(i).unwrap();
+        assert_eq!(inverse.substitute(sbox.substitute(n)), n);
+        assert_eq!(sbox.substitute(inverse.substitute(n)), n);
+    }
+    assert_eq!(inverse.inverse(), Ok(sbox));
+
+    let mut not_bijective = sbox;
+    not_bijective.0[1] = not_bijective.0[0];
+    assert_eq!(not_bijective.inverse(), Err(SBoxError { output: n(0xC) }));
+    assert_eq!(
+        not_bijective.inverse().unwrap_err().to_string(),
+        "S-box output 12 is produced by more than one input"
+    );
+}
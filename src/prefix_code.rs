@@ -0,0 +1,231 @@
+//! Everything related to the PrefixCode struct
+
+use std::fmt;
+
+use crate::{
+    bit_stream::{BitReader, BitWriter},
+    u1::u1,
+};
+
+/// A table of variable-length, prefix-free codes for byte symbols, such as a
+/// Huffman code.
+///
+/// Codes are written to the stream in slice order, so the first bit of a
+/// code is the first bit written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixCode {
+    /// The code of every symbol, indexed by the symbol
+    codes: Vec<Option<Vec<u1>>>,
+
+    /// A binary trie of the codes, rooted at index 0
+    trie: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Node {
+    /// Indices of the nodes reached by reading a zero and a one
+    Branch([Option<usize>; 2]),
+
+    /// The symbol whose code ends here
+    Leaf(u8),
+}
+
+impl PrefixCode {
+    /// Builds a code table from `(symbol, code)` pairs. Fails if a code is
+    /// empty, a symbol appears twice, or one code is a prefix of another.
+    pub fn new(codes: &[(u8, Vec<u1>)]) -> Result<Self, PrefixCodeError> {
+        let mut table = vec![None; 256];
+        let mut trie = vec![Node::Branch([None, None])];
+
+        for (symbol, code) in codes {
+            let symbol = *symbol;
+            if code.is_empty() {
+                return Err(PrefixCodeError::EmptyCode(symbol));
+            }
+            if table[usize::from(symbol)].is_some() {
+                return Err(PrefixCodeError::DuplicateSymbol(symbol));
+            }
+
+            let mut node = 0;
+            for (i, &bit) in code.iter().enumerate() {
+                let Node::Branch(children) = trie[node] else {
+                    return Err(PrefixCodeError::NotPrefixFree(symbol));
+                };
+                let is_last = i == code.len() - 1;
+                node = match children[usize::from(bit)] {
+                    Some(_) if is_last => return Err(PrefixCodeError::NotPrefixFree(symbol)),
+                    Some(child) => child,
+                    None => {
+                        let child = trie.len();
+                        trie.push(if is_last {
+                            Node::Leaf(symbol)
+                        } else {
+                            Node::Branch([None, None])
+                        });
+                        if let Node::Branch(children) = &mut trie[node] {
+                            children[usize::from(bit)] = Some(child);
+                        }
+                        child
+                    }
+                };
+            }
+            table[usize::from(symbol)] = Some(code.clone());
+        }
+
+        Ok(Self { codes: table, trie })
+    }
+
+    /// Returns the code of `symbol`, or `None` if it has no code
+    pub fn code(&self, symbol: u8) -> Option<&[u1]> {
+        self.codes[usize::from(symbol)].as_deref()
+    }
+
+    /// Writes the code of every symbol to `writer`. Nothing is written if a
+    /// symbol has no code.
+    pub fn encode(&self, symbols: &[u8], writer: &mut BitWriter) -> Result<(), PrefixCodeError> {
+        if let Some(&symbol) = symbols.iter().find(|&&s| self.code(s).is_none()) {
+            return Err(PrefixCodeError::UnknownSymbol(symbol));
+        }
+        for code in symbols.iter().filter_map(|&s| self.code(s)) {
+            code.iter().for_each(|&bit| writer.write_bit(bit));
+        }
+        Ok(())
+    }
+
+    /// Reads a single symbol from `reader` by walking the trie. Returns
+    /// `None` if the stream ends mid-code or the bits read aren't the start
+    /// of any code, without consuming anything.
+    pub fn decode_symbol(&self, reader: &mut BitReader) -> Option<u8> {
+        let mut probe = reader.clone();
+        let mut node = 0;
+        loop {
+            match self.trie[node] {
+                Node::Leaf(symbol) => {
+                    *reader = probe;
+                    return Some(symbol);
+                }
+                Node::Branch(children) => node = children[usize::from(probe.read_bit()?)]?,
+            }
+        }
+    }
+
+    /// Reads `count` symbols from `reader`, or returns `None` without
+    /// consuming anything if any of them can't be decoded
+    pub fn decode(&self, reader: &mut BitReader, count: usize) -> Option<Vec<u8>> {
+        let mut probe = reader.clone();
+        let symbols = (0..count)
+            .map(|_| self.decode_symbol(&mut probe))
+            .collect::<Option<_>>()?;
+        *reader = probe;
+        Some(symbols)
+    }
+}
+
+/// The error returned when building or encoding with a [`PrefixCode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixCodeError {
+    /// The symbol was given a code with no bits
+    EmptyCode(u8),
+
+    /// The symbol was given more than one code
+    DuplicateSymbol(u8),
+
+    /// The symbol's code is a prefix of another code, or has one as a prefix
+    NotPrefixFree(u8),
+
+    /// The symbol has no code in the table
+    UnknownSymbol(u8),
+}
+
+impl fmt::Display for PrefixCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyCode(symbol) => write!(f, "symbol {symbol} has an empty code"),
+            Self::DuplicateSymbol(symbol) => write!(f, "symbol {symbol} has more than one code"),
+            Self::NotPrefixFree(symbol) => {
+                write!(
+                    f,
+                    "code of symbol {symbol} overlaps the prefix of another code"
+                )
+            }
+            Self::UnknownSymbol(symbol) => write!(f, "symbol {symbol} has no code"),
+        }
+    }
+}
+
+impl std::error::Error for PrefixCodeError {}
+
+#[test]
+fn round_trip_works() {
+    use crate::u1::{b0, b1};
+
+    let code = PrefixCode::new(&[
+        (b'a', vec![b0]),
+        (b'b', vec![b1, b0]),
+        (b'c', vec![b1, b1, b0]),
+        (b'd', vec![b1, b1, b1]),
+    ])
+    .unwrap();
+    let message = b"abacabad";
+
+    let mut writer = BitWriter::new();
+    code.encode(message, &mut writer).unwrap();
+    assert_eq!(writer.len(), 14);
+
+    let bytes = writer.into_bytes();
+    let mut reader = BitReader::new(&bytes);
+    assert_eq!(code.decode(&mut reader, message.len()).unwrap(), message);
+}
+
+#[test]
+fn invalid_codes_are_rejected() {
+    use crate::u1::{b0, b1};
+
+    assert_eq!(
+        PrefixCode::new(&[(1, vec![b0]), (2, vec![b0, b1])]),
+        Err(PrefixCodeError::NotPrefixFree(2))
+    );
+    assert_eq!(
+        PrefixCode::new(&[(1, vec![b0, b1]), (2, vec![b0])]),
+        Err(PrefixCodeError::NotPrefixFree(2))
+    );
+    assert_eq!(
+        PrefixCode::new(&[(1, vec![b0]), (1, vec![b1])]),
+        Err(PrefixCodeError::DuplicateSymbol(1))
+    );
+    assert_eq!(
+        PrefixCode::new(&[(1, vec![])]),
+        Err(PrefixCodeError::EmptyCode(1))
+    );
+
+    let code = PrefixCode::new(&[(1, vec![b0]), (2, vec![b1, b0])]).unwrap();
+    let mut writer = BitWriter::new();
+    assert_eq!(
+        code.encode(&[1, 3], &mut writer),
+        Err(PrefixCodeError::UnknownSymbol(3))
+    );
+    assert!(writer.is_empty());
+
+    // 0b11 isn't the start of any code
+    let mut reader = BitReader::new(&[0b1100_0000]);
+    assert_eq!(code.decode_symbol(&mut reader), None);
+}
+
+#[test]
+fn failed_decode_consumes_nothing() {
+    use crate::u1::{b0, b1};
+
+    let code = PrefixCode::new(&[(1, vec![b0]), (2, vec![b1, b0])]).unwrap();
+
+    // 0b11 isn't the start of any code
+    let mut reader = BitReader::new(&[0b1100_0000]);
+    assert_eq!(code.decode_symbol(&mut reader), None);
+    assert_eq!(reader.bits_remaining(), 8);
+
+    // 0b0 decodes to 1, then 0b11 fails
+    let mut reader = BitReader::new(&[0b0110_0000]);
+    assert_eq!(code.decode(&mut reader, 2), None);
+    assert_eq!(reader.bits_remaining(), 8);
+    assert_eq!(code.decode(&mut reader, 1), Some(vec![1]));
+    assert_eq!(reader.bits_remaining(), 7);
+}
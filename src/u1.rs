@@ -17,13 +17,120 @@ pub const b0: u1 = u1(false);
 /// A u1 representing one
 pub const b1: u1 = u1(true);
 
+/// `a & b`, indexed as `AND_TABLE[a][b]`
+pub const AND_TABLE: [[u1; 2]; 2] = [[b0, b0], [b0, b1]];
+
+/// `a | b`, indexed as `OR_TABLE[a][b]`
+pub const OR_TABLE: [[u1; 2]; 2] = [[b0, b1], [b1, b1]];
+
+/// `a ^ b`, indexed as `XOR_TABLE[a][b]`
+pub const XOR_TABLE: [[u1; 2]; 2] = [[b0, b1], [b1, b0]];
+
+/// `!(a & b)`, indexed as `NAND_TABLE[a][b]`
+pub const NAND_TABLE: [[u1; 2]; 2] = [[b1, b1], [b1, b0]];
+
+impl u1 {
+    /// Returns the value of the bit as a `bool` (one is `true`)
+    pub const fn get(self) -> bool {
+        self.0
+    }
+
+    /// A 2-to-1 multiplexer: returns `if_zero` when the bit is zero
+    /// and `if_one` when it is one
+    pub fn select(self, if_zero: u1, if_one: u1) -> u1 {
+        (!self & if_zero) | (self & if_one)
+    }
+
+    /// An XNOR gate: returns one if the bits are equal.
+    /// Unlike `==`, the result is a bit rather than a `bool`, so it can feed
+    /// straight into other gates.
+    pub fn eq_gate(self, other: u1) -> u1 {
+        !(self ^ other)
+    }
+
+    /// An XOR gate: returns one if the bits differ.
+    /// Unlike `!=`, the result is a bit rather than a `bool`, so it can feed
+    /// straight into other gates.
+    pub fn ne_gate(self, other: u1) -> u1 {
+        self ^ other
+    }
+
+    /// A full adder: adds the bit, `rhs` and `carry`, returning the sum bit
+    /// and the carry out. Unlike `+`, this never panics.
+    pub fn carrying_add(self, rhs: u1, carry: u1) -> (u1, u1) {
+        let half_sum = self ^ rhs;
+        (half_sum ^ carry, (self & rhs) | (carry & half_sum))
+    }
+
+    /// A full subtractor: subtracts `rhs` and `borrow` from the bit, returning
+    /// the difference bit and the borrow out. Unlike `-`, this never panics.
+    pub fn borrowing_sub(self, rhs: u1, borrow: u1) -> (u1, u1) {
+        let half_difference = self ^ rhs;
+        (
+            half_difference ^ borrow,
+            (!self & rhs) | (borrow & !half_difference),
+        )
+    }
+
+    /// Returns `Some(f())` if the bit is one, and `None` otherwise, like [`bool::then`]
+    pub fn then<T>(self, f: impl FnOnce() -> T) -> Option<T> {
+        self.0.then(f)
+    }
+
+    /// Returns `Some(t)` if the bit is one, and `None` otherwise, like [`bool::then_some`]
+    pub fn then_some<T>(self, t: T) -> Option<T> {
+        self.0.then_some(t)
+    }
+}
+
 impl fmt::Display for u1 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0 {
-            write!(f, "1")
-        } else {
-            write!(f, "0")
+        f.pad(if self.0 { "1" } else { "0" })
+    }
+}
+
+/// The output of a two-input gate for every combination of inputs,
+/// as rows of `[a, b, gate(a, b)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruthTable(pub [[u1; 3]; 4]);
+
+/// Returns the truth table of `gate`, with the inputs counting up from
+/// `(0, 0)` to `(1, 1)`
+pub fn truth_table(gate: impl Fn(u1, u1) -> u1) -> TruthTable {
+    TruthTable([(b0, b0), (b0, b1), (b1, b0), (b1, b1)].map(|(a, b)| [a, b, gate(a, b)]))
+}
+
+/// A 4-to-1 multiplexer built from a tree of [`u1::select`]s.
+/// `select` is least significant bit first, so `[b1, b0]` picks `inputs[1]`
+/// and `[b0, b1]` picks `inputs[2]`.
+pub fn mux4(select: [u1; 2], inputs: [u1; 4]) -> u1 {
+    let [sel0, sel1] = select;
+    sel1.select(
+        sel0.select(inputs[0], inputs[1]),
+        sel0.select(inputs[2], inputs[3]),
+    )
+}
+
+/// A 1-to-4 demultiplexer, the inverse of [`mux4`]: routes `input` to the
+/// output chosen by `select` and sets every other output to zero
+pub fn demux4(select: [u1; 2], input: u1) -> [u1; 4] {
+    let [sel0, sel1] = select;
+    [
+        input & !sel1 & !sel0,
+        input & !sel1 & sel0,
+        input & sel1 & !sel0,
+        input & sel1 & sel0,
+    ]
+}
+
+impl fmt::Display for TruthTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "a b | out")?;
+        writeln!(f, "----+----")?;
+        for [a, b, out] in self.0 {
+            writeln!(f, "{a} {b} | {out}")?;
         }
+        Ok(())
     }
 }
 
@@ -222,3 +329,116 @@ impl From<u1> for isize {
         value.0.into()
     }
 }
+
+#[test]
+fn then_works() {
+    assert_eq!(b1.then(|| 5), Some(5));
+    assert_eq!(b0.then(|| 5), None);
+    assert_eq!(b1.then_some('x'), Some('x'));
+    assert_eq!(b0.then_some('x'), None);
+}
+
+#[test]
+fn get_works() {
+    assert!(b1.get());
+    assert!(!b0.get());
+    assert_eq!(u1::from(false).get(), b0.0);
+}
+
+#[test]
+fn truth_table_works() {
+    let xor = truth_table(|a, b| a ^ b);
+    assert_eq!(
+        xor.0,
+        [[b0, b0, b0], [b0, b1, b1], [b1, b0, b1], [b1, b1, b0]]
+    );
+    assert_eq!(
+        xor.to_string(),
+        "a b | out\n----+----\n0 0 | 0\n0 1 | 1\n1 0 | 1\n1 1 | 0\n"
+    );
+}
+
+#[test]
+fn select_works() {
+    assert_eq!(b0.select(b0, b1), b0);
+    assert_eq!(b1.select(b0, b1), b1);
+    assert_eq!(b0.select(b1, b0), b1);
+    assert_eq!(b1.select(b1, b0), b0);
+}
+
+#[test]
+fn eq_and_ne_gates_work() {
+    assert_eq!(
+        truth_table(u1::eq_gate).0,
+        [[b0, b0, b1], [b0, b1, b0], [b1, b0, b0], [b1, b1, b1]]
+    );
+    assert_eq!(
+        truth_table(u1::ne_gate).0,
+        [[b0, b0, b0], [b0, b1, b1], [b1, b0, b1], [b1, b1, b0]]
+    );
+}
+
+#[test]
+fn const_tables_match_operators() {
+    for a in [b0, b1] {
+        for b in [b0, b1] {
+            let (i, j) = (usize::from(a), usize::from(b));
+            assert_eq!(AND_TABLE[i][j], a & b);
+            assert_eq!(OR_TABLE[i][j], a | b);
+            assert_eq!(XOR_TABLE[i][j], a ^ b);
+            assert_eq!(NAND_TABLE[i][j], !(a & b));
+        }
+    }
+}
+
+#[test]
+fn display_honors_width_and_fill() {
+    assert_eq!(format!("{}", b1), "1");
+    assert_eq!(format!("{:>3}", b1), "  1");
+    assert_eq!(format!("{:<3}|", b0), "0  |");
+    assert_eq!(format!("{:*^5}", b1), "**1**");
+}
+
+#[test]
+fn mux4_and_demux4_work() {
+    let selects = [[b0, b0], [b1, b0], [b0, b1], [b1, b1]];
+    for (i, select) in selects.into_iter().enumerate() {
+        let inputs: [u1; 4] = std::array::from_fn(|j| u1(i == j));
+        assert_eq!(mux4(select, inputs), b1);
+        assert_eq!(mux4(select, inputs.map(|bit| !bit)), b0);
+
+        assert_eq!(demux4(select, b1), inputs);
+        assert_eq!(demux4(select, b0), [b0; 4]);
+        assert_eq!(mux4(select, demux4(select, b1)), b1);
+    }
+}
+
+#[test]
+fn carrying_add_works() {
+    for a in [b0, b1] {
+        for b in [b0, b1] {
+            for carry in [b0, b1] {
+                let total = u8::from(a) + u8::from(b) + u8::from(carry);
+                assert_eq!(
+                    a.carrying_add(b, carry),
+                    (u1(total & 1 == 1), u1(total >= 2))
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn borrowing_sub_works() {
+    for a in [b0, b1] {
+        for b in [b0, b1] {
+            for borrow in [b0, b1] {
+                let difference = i8::from(a.0) - i8::from(b.0) - i8::from(borrow.0);
+                assert_eq!(
+                    a.borrowing_sub(b, borrow),
+                    (u1(difference & 1 == 1), u1(difference < 0))
+                );
+            }
+        }
+    }
+}